@@ -0,0 +1,31 @@
+use soroban_sdk::{contract, contractimpl, vec, Address, BytesN, Env, IntoVal, Symbol, Val};
+
+#[contract]
+pub struct FactoryContract;
+
+#[contractimpl]
+impl FactoryContract {
+
+    // Deploys a freelancer contract and sets its admin in the same invocation,
+    // so nobody can call init_admin on it before the deployer does.
+    pub fn deploy(
+        e: Env,
+        deployer: Address,
+        wasm_hash: BytesN<32>,
+        salt: BytesN<32>,
+        admin: Address,
+    ) -> Address {
+        if deployer != e.current_contract_address() {
+            deployer.require_auth();
+        }
+        // init_admin needs the admin's signature, it is covered by this one
+        admin.require_auth();
+
+        let contract_id = e.deployer().with_address(deployer, salt).deploy(wasm_hash);
+
+        let args: soroban_sdk::Vec<Val> = vec![&e, admin.into_val(&e)];
+        e.invoke_contract::<()>(&contract_id, &Symbol::new(&e, "init_admin"), args);
+
+        contract_id
+    }
+}
@@ -0,0 +1,6 @@
+#![no_std]
+
+mod contract;
+mod test;
+
+pub use crate::contract::FactoryContractClient;
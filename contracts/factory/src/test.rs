@@ -0,0 +1,42 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::contract::{FactoryContract, FactoryContractClient};
+use soroban_sdk::{testutils::Address as _, Address, BytesN, Env};
+
+// Build the freelancer contract first:
+// cargo build -p freelancer --target wasm32-unknown-unknown --release
+mod freelancer {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32-unknown-unknown/release/freelancer.wasm"
+    );
+}
+
+#[test]
+fn test_deploy_sets_the_admin_in_the_same_invocation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    // The freelancer wasm is large, instantiating it a few times exceeds the default budget
+    env.budget().reset_unlimited();
+
+    let deployer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let impostor = Address::generate(&env);
+
+    let factory_address = env.register_contract(None, FactoryContract);
+    let factory_client = FactoryContractClient::new(&env, &factory_address);
+
+    let wasm_hash = env.deployer().upload_contract_wasm(freelancer::WASM);
+    let salt = BytesN::from_array(&env, &[0; 32]);
+    let contract_id = factory_client.deploy(&deployer, &wasm_hash, &salt, &admin);
+
+    let freelance_client = freelancer::Client::new(&env, &contract_id);
+    assert!(freelance_client.try_init_admin(&impostor).is_err());
+
+    freelance_client.set_require_registration(&true);
+    assert_eq!(env.auths()[0].0, admin);
+    assert!(freelance_client.get_require_registration());
+
+    // The same salt cannot be used to deploy over an existing contract
+    assert!(factory_client.try_deploy(&deployer, &wasm_hash, &salt, &admin).is_err());
+}
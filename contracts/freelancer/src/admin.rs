@@ -12,6 +12,7 @@ pub fn read_administrator(e: &Env) -> Address {
     e.storage().instance().get(&key).unwrap()
 }
 
+#[allow(clippy::needless_borrow)]
 pub fn write_administrator(e: &Env, id: &Address) {
     let key = DataKey::Admin;
     e.storage().instance().set(&key, id);
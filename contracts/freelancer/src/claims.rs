@@ -0,0 +1,57 @@
+use soroban_sdk::{Address, Env, Map};
+
+use crate::events::funds_credited;
+use crate::storage::{read_persistent, write_persistent};
+use crate::storage_types::DataKey;
+
+pub fn read_claimable(e: &Env, address: &Address) -> Map<Address, u128> {
+    read_persistent(e, &DataKey::Claimable(address.clone()))
+        .unwrap_or(Map::new(e))
+}
+
+fn write_claimable(e: &Env, address: &Address, balances: &Map<Address, u128>) {
+    let key = DataKey::Claimable(address.clone());
+    if balances.is_empty() {
+        e.storage().persistent().remove(&key);
+    } else {
+        write_persistent(e, &key, balances);
+    }
+}
+
+// Escrowed funds are never pushed, they are credited here and pulled with withdraw
+pub fn credit(e: &Env, address: &Address, project_id: u128, token: &Address, amount: u128) {
+    if amount == 0 {
+        return;
+    }
+
+    let mut balances = read_claimable(e, address);
+    let balance = balances.get(token.clone()).unwrap_or(0);
+    balances.set(token.clone(), balance + amount);
+    write_claimable(e, address, &balances);
+
+    funds_credited(e, address.clone(), project_id, token.clone(), amount);
+}
+
+pub fn take_claimable(e: &Env, address: &Address, token: &Address) -> u128 {
+    let mut balances = read_claimable(e, address);
+    let amount = balances.get(token.clone()).unwrap_or(0);
+    balances.remove(token.clone());
+    write_claimable(e, address, &balances);
+    amount
+}
+
+pub fn move_claimable(e: &Env, from: &Address, to: &Address) {
+    let moved = read_claimable(e, from);
+    if moved.is_empty() {
+        return;
+    }
+
+    let mut balances = read_claimable(e, to);
+    for (token, amount) in moved.iter() {
+        let balance = balances.get(token.clone()).unwrap_or(0);
+        balances.set(token, balance + amount);
+    }
+
+    write_claimable(e, to, &balances);
+    e.storage().persistent().remove(&DataKey::Claimable(from.clone()));
+}
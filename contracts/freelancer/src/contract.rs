@@ -1,11 +1,23 @@
 use soroban_sdk::{
-    contract, contractimpl, symbol_short, Address, Env, Map, String, Vec
+    contract, contractimpl, Address, BytesN, Env, Map, String, Vec
 };
 
-use crate::storage::{get_project, get_all_projects};
-use crate::storage_types::{Objective, Project, DataKey, User};
+use crate::admin::{has_administrator, read_administrator, write_administrator};
+use crate::storage::{add_address_project, get_project, get_all_projects, get_timesheet, next_project_id, read_persistent, read_require_registration, write_persistent, write_require_registration};
+use crate::storage_types::{DelegatePermissions, HourlyTerms, Objective, ObjectiveKind, OrgAction, OrgRole, Crowdfund, Holdback, Organization, Project, ProjectKind, ProjectTerms, Template, TemplateObjective, ProjectSummary, DataKey, Retainer, Stream, Rating, RecoveryConfig, Settlement, Reputation, TimeEntry, Timesheet, TimesheetStatus, User, UserProfile, UserRole, Withdrawal, WithdrawalReason, DAY_IN_SECONDS, WEEK_IN_SECONDS};
 use crate::token::TokenClient;
-use crate::events::{project_created, objective_added, objective_completed, objective_funded, project_cancelled, project_completed, project_refunded};
+use crate::events::{project_created, objective_added, objective_completed, objective_funded, project_cancelled, project_completed, project_refunded, stream_added, stream_cancelled, stream_withdrawn, retainer_charged, retainer_paused, retainer_resumed, hourly_funded, time_logged, week_approved, week_disputed, project_rated, user_registered, user_updated, user_deleted, recovery_set, recovery_approved, user_migrated, org_created, org_updated, org_action_approved, delegate_set, delegate_removed, registration_required_set, funds_withdrawn, template_created, template_removed, template_used, settlement_proposed, settlement_rejected, project_settled, kill_fee_paid, project_withdrawn, objective_refunded, holdback_started, holdback_released, defect_claimed, claim_settlement_proposed, claim_settled, funder_added, funder_removed, objective_sponsored, crowdfunding_opened, objective_contributed, crowdfunding_expired};
+use crate::stream::vested_amount;
+use crate::summary::{project_summary, refundable_amount, release_escrow};
+use crate::funding::{add_contribution, is_funder, read_contributions, refund_shares, remove_contributions, remove_funder, write_funder};
+use crate::crowdfund::{raised_amount, read_crowdfund, remove_crowdfund, write_crowdfund};
+use crate::holdback::{pending_holdbacks, read_claimed_holdback, read_holdback, remove_holdback, write_holdback};
+use crate::claims::{credit, read_claimable, take_claimable};
+use crate::users::{approve_recovery_candidate, clear_recovery_requests, has_role, migrate_user, normalize_handle, read_user, read_user_by_id};
+use crate::template::{read_owner_templates, read_template, scaled_price, write_owner_templates, write_template};
+use crate::delegate::{read_delegate, remove_delegate, require_client_or_delegate, write_delegate, Permission};
+use crate::organization::{add_member_org, approver_count, is_org_approver, read_organization, read_organization_by_account, require_org_owner, require_party, write_organization};
+use crate::reputation::{read_reputation, record_completed_project, record_rating, MAX_RATING, MIN_RATING};
 
 #[contract]
 pub struct FreelanceContract;
@@ -13,64 +25,155 @@ pub struct FreelanceContract;
 #[contractimpl]
 impl FreelanceContract {
 
+    // Not named initialize, the token contract in this crate already exports that symbol.
+    // The first caller becomes the admin, so deploy through the factory contract,
+    // which calls this in the same invocation as the deployment.
+    pub fn init_admin(e: Env, admin: Address) {
+        admin.require_auth();
+
+        if has_administrator(&e) {
+            panic!("already initialized");
+        }
+        write_administrator(&e, &admin);
+    }
+
+    pub fn set_require_registration(e: Env, required: bool) {
+        if !has_administrator(&e) {
+            panic!("Contract is not initialized");
+        }
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        write_require_registration(&e, required);
+
+        registration_required_set(&e, admin, required);
+    }
+
+    pub fn get_require_registration(e: Env) -> bool {
+        read_require_registration(&e)
+    }
+
     pub fn create_project(
         e: Env,
         freelancer: Address,
         prices: Vec<u128>,
+        token: Address,
         user: Address,
     ) -> u128 {
         user.require_auth(); 
 
-        if prices.is_empty() {
-            panic!("Prices cannot be empty");
+        create_milestone_project(&e, &user, &freelancer, &token, prices, ProjectTerms::default())
+    }
+
+    pub fn create_project_with_terms(e: Env, freelancer: Address, prices: Vec<u128>, terms: ProjectTerms, token: Address, user: Address) -> u128 {
+        user.require_auth();
+
+        create_milestone_project(&e, &user, &freelancer, &token, prices, terms)
+    }
+
+    pub fn create_template(e: Env, owner: Address, name: String, objectives: Vec<TemplateObjective>, delegates: Map<Address, DelegatePermissions>, terms: ProjectTerms) -> u64 {
+        owner.require_auth();
+
+        require_valid_terms(&terms);
+
+        if objectives.is_empty() {
+            panic!("Objectives cannot be empty");
         }
 
-        let contract_key = symbol_short!("pk");
-        let mut project_count: u128 = e
-            .storage()
-            .instance()
-            .get(&contract_key)
-            .unwrap_or(0);
-    
-        project_count += 1;
-        e.storage().instance().set(&contract_key, &project_count);
-        let mut objectives: Map<u128, Objective> = Map::new(&e);
-        for (i, price) in prices.iter().enumerate() {
-            objectives.set(i as u128, Objective {
-                price: price as u128,
-                half_paid: 0,
-                completed: false,
-            });
+        if delegates.contains_key(owner.clone()) {
+            panic!("Project parties cannot be delegates");
         }
-        
-        let project = Project {
-            client: user.clone(),
-            freelancer: freelancer.clone(),
-            objectives_count: prices.len() as u128,
+
+        let template_id: u64 = read_persistent(&e, &DataKey::TemplateCounter)
+            .unwrap_or(0)
+            + 1;
+
+        write_persistent(&e, &DataKey::TemplateCounter, &template_id);
+
+        let objectives_count = objectives.len();
+        write_template(&e, &Template {
+            id: template_id,
+            owner: owner.clone(),
+            name,
             objectives,
-            completed_objectives: 0,
-            earned_amount: 0,
-            contract_balance: 0,
-            cancelled: false,
-            completed: false,
-        };
-        
-        let project_key = DataKey::Project(project_count);
-        e.storage().instance().set(&project_key, &project);
+            delegates,
+            terms,
+        });
 
-        project_created(&e, project_key, user.clone(), freelancer.clone(), prices);
+        let mut template_ids = read_owner_templates(&e, &owner);
+        template_ids.push_back(template_id);
+        write_owner_templates(&e, &owner, &template_ids);
 
-        project_count
+        template_created(&e, template_id, owner, objectives_count);
+
+        template_id
     }
 
-    pub fn complete_project(e: Env, project_id: u128, user: Address) {
-        let (mut project, project_key) = get_project(&e, project_id);
+    pub fn remove_template(e: Env, template_id: u64) {
+        let template = read_template(&e, template_id);
+        template.owner.require_auth();
+
+        e.storage()
+            .persistent()
+            .remove(&DataKey::Template(template_id));
+
+        let mut template_ids = read_owner_templates(&e, &template.owner);
+        if let Some(index) = template_ids.first_index_of(template_id) {
+            template_ids.remove(index);
+        }
+        write_owner_templates(&e, &template.owner, &template_ids);
+
+        template_removed(&e, template_id, template.owner);
+    }
+
+    pub fn get_template(e: Env, template_id: u64) -> Template {
+        read_template(&e, template_id)
+    }
+
+    pub fn get_templates(e: Env, owner: Address) -> Vec<Template> {
+        let mut templates: Vec<Template> = Vec::new(&e);
+
+        for template_id in read_owner_templates(&e, &owner).iter() {
+            templates.push_back(read_template(&e, template_id));
+        }
+
+        templates
+    }
+
+    pub fn create_project_from_template(e: Env, template_id: u64, freelancer: Address, token: Address, scale: u32) -> u128 {
+        let template = read_template(&e, template_id);
+        template.owner.require_auth();
+
+        if scale == 0 {
+            panic!("Scale must be greater than zero");
+        }
 
-        let invoker = user;
-        if invoker != project.client {
-            panic!("Only the client can mark the project as completed");
+        if template.delegates.contains_key(freelancer.clone()) {
+            panic!("Project parties cannot be delegates");
         }
 
+        let mut prices: Vec<u128> = Vec::new(&e);
+        for objective in template.objectives.iter() {
+            prices.push_back(scaled_price(objective.price, scale));
+        }
+
+        let project_id = create_milestone_project(&e, &template.owner, &freelancer, &token, prices, template.terms.clone());
+        let (project, _) = get_project(&e, project_id);
+
+        for (delegate, permissions) in template.delegates.iter() {
+            write_delegate(&e, project_id, &delegate, &permissions);
+            delegate_set(&e, project_id, &project, delegate, permissions);
+        }
+
+        template_used(&e, template_id, template.owner, project_id, scale);
+
+        project_id
+    }
+
+    pub fn complete_project(e: Env, project_id: u128) {
+        let (mut project, project_key) = get_project(&e, project_id);
+        project.client.require_auth();
+
         if project.completed {
             panic!("Project is completed");
         }
@@ -79,79 +182,168 @@ impl FreelanceContract {
             panic!("Project is cancelled");
         }
 
-        if project.completed_objectives != project.objectives_count {
+        // A retainer's objective repeats every period and is never completed itself,
+        // completing the retainer just stops the charges
+        let is_retainer = matches!(project.kind, ProjectKind::Retainer(_));
+        if !is_retainer && project.completed_objectives != project.objectives_count {
             panic!("Not all objectives completed");
         }
 
+        // Hourly escrow no approved week was paid from goes back to the client
+        if project.contract_balance > 0 {
+            let unspent = project.contract_balance;
+            project.contract_balance = 0;
+            credit(&e, &project.client, project_id, &project.token, unspent);
+            project_refunded(&e, project_id, &project, project.client.clone(), unspent);
+        }
+
         project.completed = true;
         e.storage().instance().set(&project_key, &project);
-        project_completed(&e, project_key);
+        clear_contributions(&e, project_id, &project);
+
+        record_completed_project(&e, &project.client, project.earned_amount);
+        record_completed_project(&e, &project.freelancer, project.earned_amount);
+
+        project_completed(&e, project_id, &project);
 
     }
 
-    pub fn complete_objective(
-        e: Env,
-        project_id: u128,
-        objective_id: u128,
-        user: Address,
-        usdc_contract: Address,
-        freelance_contract_address: Address,
-        freelancer_address: Address
-    ) {
-        user.require_auth();
-    
-        let project_key = DataKey::Project(project_id);
-        let mut project: Project = e.storage().instance().get(&project_key).unwrap();
-    
-        if freelancer_address != project.freelancer {
-            panic!("Only the freelancer can complete objectives");
+    pub fn complete_objective(e: Env, project_id: u128, objective_id: u128, user: Address) {
+        let (project, _) = get_project(&e, project_id);
+        require_client_or_delegate(&e, project_id, &project, &user, Permission::ApproveObjectives);
+
+        // Delegates approve but never pay, the rest always comes from the client's allowance.
+        // Organizations approve through their members, a delegate would skip the threshold.
+        require_not_org_client(&e, &project);
+
+        complete_objectives_from_client(&e, project_id, &Vec::from_array(&e, [objective_id]));
+    }
+
+    pub fn complete_objectives(e: Env, project_id: u128, objective_ids: Vec<u128>, user: Address) {
+        let (project, _) = get_project(&e, project_id);
+        require_client_or_delegate(&e, project_id, &project, &user, Permission::ApproveObjectives);
+        require_not_org_client(&e, &project);
+
+        complete_objectives_from_client(&e, project_id, &objective_ids);
+    }
+
+    pub fn cancel_project(e: Env, project_id: u128) {
+        let (mut project, project_key) = get_project(&e, project_id);
+        project.client.require_auth();
+
+        if project.completed {
+            panic!("Project is completed");
         }
-    
-        let mut objective = project.objectives.get(objective_id).unwrap();
-    
-        if objective.half_paid == 0 {
-            panic!("Objective not funded");
+
+        if project.cancelled {
+            panic!("Project is cancelled");
         }
-    
-        if objective.completed {
-            panic!("Objective already completed");
+
+        // Funded milestones the freelancer was working on are split by the agreed kill fee
+        for (objective_id, mut objective) in project.objectives.clone().iter() {
+            if objective.completed || objective.half_paid == 0 {
+                continue;
+            }
+
+            if let ObjectiveKind::Stream(_) = objective.kind {
+                continue;
+            }
+
+            let fee = objective.half_paid * project.terms.kill_fee_percent as u128 / 100;
+            let refunded = objective.half_paid - fee;
+
+            credit(&e, &project.freelancer, project_id, &project.token, fee);
+
+            project.earned_amount += fee;
+            objective.half_paid = refunded;
+            project.objectives.set(objective_id, objective);
+
+            kill_fee_paid(&e, project_id, &project, objective_id, fee, refunded);
         }
-    
-        let remaining_price = (objective.price - objective.half_paid) as i128;
-        let full_price = objective.price;
-    
-        let usdc_client = TokenClient::new(&e, &usdc_contract);
-        usdc_client.transfer(
-            &user,              
-            &freelance_contract_address,
-            &remaining_price
-        );
 
-        let expiration_ledger = e.ledger().sequence() + 1000;
-        usdc_client.approve(&freelance_contract_address, &freelancer_address, &remaining_price, &expiration_ledger);
-        usdc_client.transfer(
-            &freelance_contract_address,
-            &freelancer_address,
-            &(objective.price as i128)
-        );
-    
-        objective.completed = true;
-        project.completed_objectives += 1;
-        project.earned_amount += objective.price;
-    
-        project.objectives.set(objective_id, objective);
+        // Whatever the freelancer has not earned goes back to the client right away
+        refund_uncompleted_escrow(&e, project_id, &mut project);
+        clear_contributions(&e, project_id, &project);
+
+        project.cancelled = true;
+
         e.storage().instance().set(&project_key, &project);
-    
-        objective_completed(&e, project_key, objective_id, full_price);
+        project_cancelled(&e, project_id, &project);
+    }
+
+    pub fn propose_settlement(e: Env, project_id: u128, user: Address, to_freelancer: u128, to_client: u128) {
+        user.require_auth();
+        let (project, _) = get_project(&e, project_id);
+
+        if user != project.client && user != project.freelancer {
+            panic!("Only the client or the freelancer can propose a settlement");
+        }
+
+        if project.completed {
+            panic!("Project is completed");
+        }
+
+        if project.cancelled {
+            panic!("Project is cancelled");
+        }
+
+        // The proposal has to split exactly what the project holds in escrow
+        let escrowed = project_summary(&e, project_id, &project).escrowed;
+        if to_freelancer + to_client != escrowed {
+            panic!("Settlement must split the whole escrow");
+        }
+
+        // A new proposal from either party replaces the pending one
+        write_persistent(&e, &DataKey::Settlement(project_id), &Settlement {
+            proposer: user.clone(),
+            to_freelancer,
+            to_client,
+            timestamp: e.ledger().timestamp(),
+        });
+
+        settlement_proposed(&e, project_id, &project, user, to_freelancer, to_client);
     }
 
-    pub fn cancel_project(e: Env, project_id: u128, user: Address) {
+    pub fn reject_settlement(e: Env, project_id: u128, user: Address) {
+        user.require_auth();
+        let (project, _) = get_project(&e, project_id);
+
+        if user != project.client && user != project.freelancer {
+            panic!("Only the client or the freelancer can reject a settlement");
+        }
+
+        let settlement_key = DataKey::Settlement(project_id);
+        let settlement: Settlement = match read_persistent(&e, &settlement_key) {
+            Some(settlement) => settlement,
+            None => panic!("No settlement proposed"),
+        };
+
+        e.storage().persistent().remove(&settlement_key);
+
+        settlement_rejected(&e, project_id, &project, user, settlement.to_freelancer, settlement.to_client);
+    }
+
+    // The accepted amounts are passed again so a replaced proposal is never accepted by mistake
+    pub fn accept_settlement(e: Env, project_id: u128, user: Address, to_freelancer: u128, to_client: u128) {
         user.require_auth();
         let (mut project, project_key) = get_project(&e, project_id);
 
-        let invoker = user;
-        if invoker != project.client {
-            panic!("Only the client can mark the project as completed");
+        let settlement_key = DataKey::Settlement(project_id);
+        let settlement: Settlement = match read_persistent(&e, &settlement_key) {
+            Some(settlement) => settlement,
+            None => panic!("No settlement proposed"),
+        };
+
+        if user != project.client && user != project.freelancer {
+            panic!("Only the client or the freelancer can accept a settlement");
+        }
+
+        if user == settlement.proposer {
+            panic!("The settlement must be accepted by the other party");
+        }
+
+        if settlement.to_freelancer != to_freelancer || settlement.to_client != to_client {
+            panic!("Settlement does not match the proposal");
         }
 
         if project.completed {
@@ -162,20 +354,45 @@ impl FreelanceContract {
             panic!("Project is cancelled");
         }
 
+        let released = release_escrow(&e, project_id, &mut project, e.ledger().timestamp());
+        let total = released.values().iter().sum::<u128>() + project.contract_balance;
+        project.contract_balance = 0;
+
+        if total != to_freelancer + to_client {
+            panic!("Escrow changed since the settlement was proposed");
+        }
+
+        // The client's share is split like the escrow, funders get back their part of each objective
+        let mut to_client_left = to_client;
+        let mut client_refund: u128 = 0;
+        for (objective_id, amount) in released.iter() {
+            let share = to_client * amount / total;
+            to_client_left -= share;
+            client_refund += refund_objective(&e, project_id, &project, objective_id, share);
+        }
+        client_refund += to_client_left;
+
+        credit(&e, &project.freelancer, project_id, &project.token, to_freelancer);
+        credit(&e, &project.client, project_id, &project.token, client_refund);
+        clear_contributions(&e, project_id, &project);
+
+        project.earned_amount += to_freelancer;
         project.cancelled = true;
+        project.terminated = true;
 
+        e.storage().persistent().remove(&settlement_key);
         e.storage().instance().set(&project_key, &project);
-         project_cancelled(&e, project_key);
+
+        project_settled(&e, project_id, &project, user, to_freelancer, to_client);
+    }
+
+    pub fn get_settlement(e: Env, project_id: u128) -> Option<Settlement> {
+        read_persistent(&e, &DataKey::Settlement(project_id))
     }
 
     pub fn add_objective(e: Env, project_id: u128, prices: Vec<u128>, user: Address) {
-        user.require_auth();
         let (mut project, project_key) = get_project(&e, project_id);
-
-        let invoker = user;
-        if invoker != project.client {
-            panic!("Only the client can add objectives");
-        }
+        require_client_or_delegate(&e, project_id, &project, &user, Permission::AddObjectives);
 
         if project.completed {
             panic!("Project is completed");
@@ -184,181 +401,1728 @@ impl FreelanceContract {
         if project.cancelled {
             panic!("Project is cancelled");
         }
+
+        require_milestones(&project);
         
         for (i, price) in prices.iter().enumerate() {
             let objective_id = project.objectives_count + i as u128;
 
             project.objectives.set(objective_id, Objective {
-                price: price,
+                price,
                 half_paid: 0,
                 completed: false,
+                kind: ObjectiveKind::Milestone,
             });
 
-            objective_added(&e, &project_key, objective_id, price);
+            objective_added(&e, project_id, &project, objective_id, price);
         }
 
         project.objectives_count += prices.len() as u128;
         e.storage().instance().set(&project_key, &project);
     }
 
-    pub fn fund_objective(e: Env, project_id: u128, objective_id: u128, user: Address, usdc_contract: Address, freelance_contract_address: Address) {
-        user.require_auth();
-    
-        let project_key = DataKey::Project(project_id);
-        let mut project: Project = e.storage().instance().get(&project_key).unwrap();
-    
-        if user != project.client {
-            panic!("Only the client can fund objectives");
+    pub fn add_stream_objective(e: Env, project_id: u128, price: u128, start_time: u64, end_time: u64, user: Address) -> u128 {
+        let (mut project, project_key) = get_project(&e, project_id);
+        require_client_or_delegate(&e, project_id, &project, &user, Permission::AddObjectives);
+
+        if project.completed {
+            panic!("Project is completed");
         }
-    
-        let mut objective = project.objectives.get(objective_id).unwrap();
-        if objective.half_paid > 0 {
-            panic!("Objective already funded");
+
+        if project.cancelled {
+            panic!("Project is cancelled");
         }
-    
-        let half_price = (objective.price / 2) as i128;
-        let usdc_client = TokenClient::new(&e, &usdc_contract);
 
-        let allowance = usdc_client.allowance(&user, &freelance_contract_address);
-        if allowance < half_price {
-            panic!("Not enough allowance to fund this objective. Please approve the amount first.");
+        require_milestones(&project);
+
+        if price == 0 {
+            panic!("Price must be greater than zero");
         }
-    
-        usdc_client.transfer(
-            &user,              
-            &freelance_contract_address,
-            &half_price       
-        );
 
-        usdc_client.approve(&user, &freelance_contract_address, &0, &e.ledger().sequence());
-    
-        objective.half_paid = half_price as u128;
-        project.objectives.set(objective_id, objective);
+        if end_time <= start_time {
+            panic!("Stream end time must be after its start time");
+        }
+
+        let objective_id = project.objectives_count;
+        project.objectives.set(objective_id, Objective {
+            price,
+            half_paid: 0,
+            completed: false,
+            kind: ObjectiveKind::Stream(Stream {
+                start_time,
+                end_time,
+                withdrawn: 0,
+                cancelled_at: None,
+            }),
+        });
+
+        project.objectives_count += 1;
         e.storage().instance().set(&project_key, &project);
-    
-        objective_funded(&e, project_key, objective_id, half_price as u128);
-    }
 
-    pub fn refund_remaining_funds(e: Env, project_id: u128, objective_id: u128, user: Address, usdc_contract: Address, freelance_contract_address: Address) {
-        user.require_auth();
-        let (project, project_key) = get_project(&e, project_id);
+        stream_added(&e, project_id, &project, objective_id, price, start_time, end_time);
 
-        let invoker = user.clone();
-        if invoker != project.client {
-            panic!("Only the client can mark the project as completed");
-        }
+        objective_id
+    }
 
-        if !project.cancelled {
-            panic!("Project is cancelled");
+    pub fn withdraw_vested(e: Env, project_id: u128, objective_id: u128, user: Address) -> u128 {
+        let (mut project, project_key) = get_project(&e, project_id);
+        require_party(&e, &project.freelancer, &user);
+
+        if project.terminated {
+            panic!("Project was settled");
         }
 
+        let mut objective = project.objectives.get(objective_id).unwrap();
+        let mut stream = match objective.kind.clone() {
+            ObjectiveKind::Stream(stream) => stream,
+            ObjectiveKind::Milestone => panic!("Objective is not a stream"),
+        };
 
-        let mut refundable_amount : i128 = 0;
-        for _i in 0..project.objectives_count {
-            let mut objective = project.objectives.get(objective_id).unwrap(); 
-            
-            if !objective.completed && objective.half_paid > 0 {
-                refundable_amount += objective.half_paid as i128;
-                objective.half_paid = 0; 
-            }
+        if objective.half_paid == 0 {
+            panic!("Objective not funded");
         }
-        
-        let usdc_client = TokenClient::new(&e, &usdc_contract);
-        let contract_balance = usdc_client.balance(&freelance_contract_address);
-        if  contract_balance == 0 {
-            panic!("The contract has no balance to repay");
+
+        // Never more than what is still escrowed for the stream
+        let vested = vested_amount(&stream, objective.price, e.ledger().timestamp());
+        let available = vested.min(objective.half_paid).saturating_sub(stream.withdrawn);
+        if available == 0 {
+            panic!("Nothing vested to withdraw");
         }
 
-        usdc_client.transfer(
-            &e.current_contract_address(),
-            &project.client,
-            &(contract_balance as i128) 
-        );
+        credit(&e, &project.freelancer, project_id, &project.token, available);
 
-        project_refunded(&e, project_key, user.clone(), refundable_amount as u128);
+        stream.withdrawn += available;
+        project.earned_amount += available;
 
-    }
-    
-    pub fn get_projects_by_freelancer(e: Env, freelancer: Address) -> Vec<Project> {
-        let all_projects: Vec<Project> = get_all_projects(e.clone());
-    
-        let mut result: Vec<Project> = Vec::new(&e);
-    
-        for project in all_projects.iter() {
-            if project.freelancer == freelancer {
-                result.push_back(project);
-            }
+        if stream.withdrawn == objective.half_paid {
+            objective.completed = true;
+            project.completed_objectives += 1;
         }
-    
-        result
-    }
 
-    pub fn get_projects_by_client(e: Env, client: Address) -> Vec<Project> {
-        let all_projects: Vec<Project> = get_all_projects(e.clone());
-    
-        let mut result: Vec<Project> = Vec::new(&e);
-    
-        for project in all_projects.iter() {
-            if project.client == client {
-                result.push_back(project);
-            }
-        }
-    
-        result
+        objective.kind = ObjectiveKind::Stream(stream);
+        project.objectives.set(objective_id, objective);
+        e.storage().instance().set(&project_key, &project);
+
+        stream_withdrawn(&e, project_id, &project, objective_id, available);
+
+        available
     }
-      
-    pub fn register(e: Env, user_address: Address, name: String, email: String) -> bool {
-        user_address.require_auth();
 
-        let key = DataKey::User(user_address.clone());
+    pub fn cancel_stream(e: Env, project_id: u128, objective_id: u128) -> u128 {
+        let (mut project, project_key) = get_project(&e, project_id);
+        project.client.require_auth();
 
-        if e.storage().persistent().has(&key) {
-            return false;
+        let mut objective = project.objectives.get(objective_id).unwrap();
+        let mut stream = match objective.kind.clone() {
+            ObjectiveKind::Stream(stream) => stream,
+            ObjectiveKind::Milestone => panic!("Objective is not a stream"),
+        };
+
+        if stream.cancelled_at.is_some() {
+            panic!("Stream already cancelled");
         }
 
-        let user_id = e
-            .storage()
-            .persistent()
-            .get(&DataKey::UserCounter)
-            .unwrap_or(0)
+        if objective.completed {
+            panic!("Objective already completed");
+        }
+
+        let now = e.ledger().timestamp();
+        let mut refunded: u128 = 0;
+
+        if objective.half_paid > 0 {
+            let vested = vested_amount(&stream, objective.price, now);
+            refunded = objective.half_paid - vested;
+            let to_client = refund_objective(&e, project_id, &project, objective_id, refunded);
+            credit(&e, &project.client, project_id, &project.token, to_client);
+
+            // Only the vested part stays in escrow for the freelancer
+            objective.half_paid = vested;
+        }
+
+        stream.cancelled_at = Some(now);
+
+        if stream.withdrawn == objective.half_paid {
+            objective.completed = true;
+            project.completed_objectives += 1;
+        }
+
+        objective.kind = ObjectiveKind::Stream(stream);
+        project.objectives.set(objective_id, objective);
+        e.storage().instance().set(&project_key, &project);
+
+        stream_cancelled(&e, project_id, &project, objective_id, refunded);
+
+        refunded
+    }
+
+    pub fn fund_objective(e: Env, project_id: u128, objective_id: u128) {
+        let (project, _) = get_project(&e, project_id);
+        project.client.require_auth();
+
+        require_not_org_client(&e, &project);
+
+        fund_objectives_from_client(&e, project_id, &Vec::from_array(&e, [objective_id]));
+    }
+
+    pub fn fund_objectives(e: Env, project_id: u128, objective_ids: Vec<u128>) {
+        let (project, _) = get_project(&e, project_id);
+        project.client.require_auth();
+
+        require_not_org_client(&e, &project);
+
+        fund_objectives_from_client(&e, project_id, &objective_ids);
+    }
+
+    pub fn add_funder(e: Env, project_id: u128, funder: Address) {
+        let (project, _) = get_project(&e, project_id);
+        project.client.require_auth();
+
+        if funder == project.client || funder == project.freelancer {
+            panic!("Project parties cannot be funders");
+        }
+
+        write_funder(&e, project_id, &funder);
+
+        funder_added(&e, project_id, &project, funder);
+    }
+
+    pub fn remove_funder(e: Env, project_id: u128, funder: Address) {
+        let (project, _) = get_project(&e, project_id);
+        project.client.require_auth();
+
+        if !is_funder(&e, project_id, &funder) {
+            panic!("Not a funder of this project");
+        }
+
+        remove_funder(&e, project_id, &funder);
+
+        funder_removed(&e, project_id, &project, funder);
+    }
+
+    pub fn is_funder(e: Env, project_id: u128, funder: Address) -> bool {
+        is_funder(&e, project_id, &funder)
+    }
+
+    // An authorized funder escrows an objective on the client's behalf and gets its refunds
+    pub fn sponsor_objective(e: Env, project_id: u128, objective_id: u128, funder: Address) {
+        funder.require_auth();
+        let (mut project, project_key) = get_project(&e, project_id);
+        require_milestones(&project);
+
+        if !is_funder(&e, project_id, &funder) {
+            panic!("Funder is not authorized by the client");
+        }
+
+        if project.completed {
+            panic!("Project is completed");
+        }
+
+        if project.cancelled {
+            panic!("Project is cancelled");
+        }
+
+        require_not_crowdfunded(&e, project_id, objective_id);
+        let amount = escrow_objective(&mut project, objective_id);
+        pull_from_client(&e, &project.token, &funder, amount);
+        add_contribution(&e, project_id, objective_id, &funder, amount);
+        e.storage().instance().set(&project_key, &project);
+
+        objective_funded(&e, project_id, &project, objective_id, amount);
+        objective_sponsored(&e, project_id, &project, objective_id, funder, amount);
+    }
+
+    pub fn get_contributions(e: Env, project_id: u128, objective_id: u128) -> Map<Address, u128> {
+        read_contributions(&e, project_id, objective_id)
+    }
+
+    // Opens an unfunded milestone to contributions from anyone until the deadline
+    pub fn open_crowdfunding(e: Env, project_id: u128, objective_id: u128, deadline: u64) {
+        let (project, _) = get_project(&e, project_id);
+        project.client.require_auth();
+        require_milestones(&project);
+
+        if project.completed {
+            panic!("Project is completed");
+        }
+
+        if project.cancelled {
+            panic!("Project is cancelled");
+        }
+
+        let objective = match project.objectives.get(objective_id) {
+            Some(objective) => objective,
+            None => panic!("Objective not found"),
+        };
+
+        if let ObjectiveKind::Stream(_) = objective.kind {
+            panic!("Streaming objectives cannot be crowdfunded");
+        }
+
+        if objective.half_paid > 0 || objective.completed {
+            panic!("Objective already funded");
+        }
+
+        if read_crowdfund(&e, project_id, objective_id).is_some() {
+            panic!("Objective is already crowdfunded");
+        }
+
+        if deadline <= e.ledger().timestamp() {
+            panic!("Deadline must be in the future");
+        }
+
+        write_crowdfund(&e, project_id, objective_id, &Crowdfund { raised: 0, deadline });
+
+        crowdfunding_opened(&e, project_id, &project, objective_id, deadline);
+    }
+
+    // Takes at most what the objective still needs and returns the amount taken.
+    // The contribution that reaches the price escrows the whole price and activates the objective.
+    pub fn contribute(e: Env, project_id: u128, objective_id: u128, contributor: Address, amount: u128) -> u128 {
+        contributor.require_auth();
+        let (mut project, project_key) = get_project(&e, project_id);
+
+        if project.cancelled {
+            panic!("Project is cancelled");
+        }
+
+        let mut crowdfund = match read_crowdfund(&e, project_id, objective_id) {
+            Some(crowdfund) => crowdfund,
+            None => panic!("Objective is not crowdfunded"),
+        };
+
+        if e.ledger().timestamp() >= crowdfund.deadline {
+            panic!("Crowdfunding has expired");
+        }
+
+        if amount == 0 {
+            panic!("Contribution must be positive");
+        }
+
+        let mut objective = project.objectives.get(objective_id).unwrap();
+        let taken = amount.min(objective.price - crowdfund.raised);
+
+        pull_from_client(&e, &project.token, &contributor, taken);
+        add_contribution(&e, project_id, objective_id, &contributor, taken);
+        crowdfund.raised += taken;
+
+        objective_contributed(&e, project_id, &project, objective_id, contributor, taken, crowdfund.raised);
+
+        if crowdfund.raised < objective.price {
+            write_crowdfund(&e, project_id, objective_id, &crowdfund);
+            return taken;
+        }
+
+        // Fully funded, nothing is left for the client to pay on completion
+        remove_crowdfund(&e, project_id, objective_id);
+        objective.half_paid = objective.price;
+        project.objectives.set(objective_id, objective);
+        e.storage().instance().set(&project_key, &project);
+
+        objective_funded(&e, project_id, &project, objective_id, crowdfund.raised);
+
+        taken
+    }
+
+    // Anyone can return the contributions once the deadline passed without reaching the price
+    pub fn expire_crowdfunding(e: Env, project_id: u128, objective_id: u128) -> u128 {
+        let (project, _) = get_project(&e, project_id);
+
+        let crowdfund = match read_crowdfund(&e, project_id, objective_id) {
+            Some(crowdfund) => crowdfund,
+            None => panic!("Objective is not crowdfunded"),
+        };
+
+        if e.ledger().timestamp() < crowdfund.deadline {
+            panic!("Crowdfunding is still open");
+        }
+
+        refund_objective(&e, project_id, &project, objective_id, crowdfund.raised);
+
+        // The objective can be funded again from scratch
+        remove_crowdfund(&e, project_id, objective_id);
+        remove_contributions(&e, project_id, objective_id);
+
+        crowdfunding_expired(&e, project_id, &project, objective_id, crowdfund.raised);
+
+        crowdfund.raised
+    }
+
+    pub fn get_crowdfund(e: Env, project_id: u128, objective_id: u128) -> Option<Crowdfund> {
+        read_crowdfund(&e, project_id, objective_id)
+    }
+
+    pub fn withdraw_from_project(e: Env, project_id: u128, reason: WithdrawalReason) -> u128 {
+        let (mut project, project_key) = get_project(&e, project_id);
+        project.freelancer.require_auth();
+
+        if project.completed {
+            panic!("Project is completed");
+        }
+
+        if project.cancelled {
+            panic!("Project is cancelled");
+        }
+
+        // Completed payouts and vested stream funds stay with the freelancer
+        let refunded = refund_uncompleted_escrow(&e, project_id, &mut project);
+        clear_contributions(&e, project_id, &project);
+
+        project.cancelled = true;
+        project.withdrawn = true;
+
+        write_persistent(&e, &DataKey::Withdrawal(project_id), &Withdrawal {
+            reason,
+            timestamp: e.ledger().timestamp(),
+        });
+        e.storage().instance().set(&project_key, &project);
+
+        project_withdrawn(&e, project_id, &project, reason, refunded);
+
+        refunded
+    }
+
+    pub fn get_withdrawal(e: Env, project_id: u128) -> Option<Withdrawal> {
+        read_persistent(&e, &DataKey::Withdrawal(project_id))
+    }
+
+    // Anyone can release a holdback once its period is over without a defect claim
+    pub fn release_holdback(e: Env, project_id: u128, objective_id: u128) -> u128 {
+        let (mut project, project_key) = get_project(&e, project_id);
+
+        let holdback = match read_holdback(&e, project_id, objective_id) {
+            Some(holdback) => holdback,
+            None => panic!("No holdback for this objective"),
+        };
+
+        if holdback.claimed {
+            panic!("Holdback has a defect claim");
+        }
+
+        if e.ledger().timestamp() < holdback.release_at {
+            panic!("Holdback period has not elapsed yet");
+        }
+
+        credit(&e, &project.freelancer, project_id, &project.token, holdback.amount);
+        project.earned_amount += holdback.amount;
+
+        remove_holdback(&e, project_id, objective_id);
+        if project.completed || project.cancelled {
+            remove_contributions(&e, project_id, objective_id);
+        }
+        e.storage().instance().set(&project_key, &project);
+
+        holdback_released(&e, project_id, &project, objective_id, project.freelancer.clone(), holdback.amount);
+
+        holdback.amount
+    }
+
+    pub fn raise_defect_claim(e: Env, project_id: u128, objective_id: u128, claim_hash: BytesN<32>) {
+        let (project, _) = get_project(&e, project_id);
+        project.client.require_auth();
+
+        let mut holdback = match read_holdback(&e, project_id, objective_id) {
+            Some(holdback) => holdback,
+            None => panic!("No holdback for this objective"),
+        };
+
+        if holdback.claimed {
+            panic!("Defect claim already raised");
+        }
+
+        if e.ledger().timestamp() >= holdback.release_at {
+            panic!("Holdback period is over");
+        }
+
+        holdback.claim_hash = claim_hash.clone();
+        holdback.claimed = true;
+        write_holdback(&e, project_id, objective_id, &holdback);
+
+        defect_claimed(&e, project_id, &project, objective_id, claim_hash);
+    }
+
+    // A claim is settled by the party giving in: the client drops it and the freelancer is paid,
+    // or the freelancer accepts it and the holdback goes back to the client
+    pub fn resolve_defect_claim(e: Env, project_id: u128, objective_id: u128, user: Address) -> u128 {
+        user.require_auth();
+        let (mut project, project_key) = get_project(&e, project_id);
+
+        let holdback = read_claimed_holdback(&e, project_id, objective_id);
+
+        let recipient = if user == project.client {
+            project.freelancer.clone()
+        } else if user == project.freelancer {
+            project.client.clone()
+        } else {
+            panic!("Only the client or the freelancer can resolve a defect claim");
+        };
+
+        // A conceded claim is a refund, it goes back to whoever funded the objective
+        if recipient == project.freelancer {
+            credit(&e, &recipient, project_id, &project.token, holdback.amount);
+            project.earned_amount += holdback.amount;
+        } else {
+            let to_client = refund_objective(&e, project_id, &project, objective_id, holdback.amount);
+            credit(&e, &recipient, project_id, &project.token, to_client);
+        }
+
+        remove_holdback(&e, project_id, objective_id);
+        e.storage().persistent().remove(&DataKey::ClaimSettlement(project_id, objective_id));
+        if project.completed || project.cancelled {
+            remove_contributions(&e, project_id, objective_id);
+        }
+        e.storage().instance().set(&project_key, &project);
+
+        holdback_released(&e, project_id, &project, objective_id, recipient, holdback.amount);
+
+        holdback.amount
+    }
+
+    // When neither party gives in, they can agree on a split of the disputed holdback.
+    // This works on completed and cancelled projects too, unlike a project settlement.
+    pub fn propose_claim_settlement(e: Env, project_id: u128, objective_id: u128, user: Address, to_freelancer: u128, to_client: u128) {
+        user.require_auth();
+        let (project, _) = get_project(&e, project_id);
+
+        if user != project.client && user != project.freelancer {
+            panic!("Only the client or the freelancer can propose a claim settlement");
+        }
+
+        let holdback = read_claimed_holdback(&e, project_id, objective_id);
+
+        if to_freelancer + to_client != holdback.amount {
+            panic!("Settlement must split the whole holdback");
+        }
+
+        // A new proposal from either party replaces the pending one
+        write_persistent(&e, &DataKey::ClaimSettlement(project_id, objective_id), &Settlement {
+            proposer: user.clone(),
+            to_freelancer,
+            to_client,
+            timestamp: e.ledger().timestamp(),
+        });
+
+        claim_settlement_proposed(&e, project_id, &project, objective_id, user, to_freelancer, to_client);
+    }
+
+    // The accepted amounts are passed again so a replaced proposal is never accepted by mistake
+    pub fn accept_claim_settlement(e: Env, project_id: u128, objective_id: u128, user: Address, to_freelancer: u128, to_client: u128) {
+        user.require_auth();
+        let (mut project, project_key) = get_project(&e, project_id);
+
+        let settlement_key = DataKey::ClaimSettlement(project_id, objective_id);
+        let settlement: Settlement = match read_persistent(&e, &settlement_key) {
+            Some(settlement) => settlement,
+            None => panic!("No claim settlement proposed"),
+        };
+
+        if user != project.client && user != project.freelancer {
+            panic!("Only the client or the freelancer can accept a claim settlement");
+        }
+
+        if user == settlement.proposer {
+            panic!("The settlement must be accepted by the other party");
+        }
+
+        if settlement.to_freelancer != to_freelancer || settlement.to_client != to_client {
+            panic!("Settlement does not match the proposal");
+        }
+
+        read_claimed_holdback(&e, project_id, objective_id);
+
+        credit(&e, &project.freelancer, project_id, &project.token, to_freelancer);
+        project.earned_amount += to_freelancer;
+
+        // The client's share is a refund, it goes back to whoever funded the objective
+        let refunded = refund_objective(&e, project_id, &project, objective_id, to_client);
+        credit(&e, &project.client, project_id, &project.token, refunded);
+
+        remove_holdback(&e, project_id, objective_id);
+        e.storage().persistent().remove(&settlement_key);
+        if project.completed || project.cancelled {
+            remove_contributions(&e, project_id, objective_id);
+        }
+        e.storage().instance().set(&project_key, &project);
+
+        claim_settled(&e, project_id, &project, objective_id, user, to_freelancer, to_client);
+    }
+
+    pub fn get_claim_settlement(e: Env, project_id: u128, objective_id: u128) -> Option<Settlement> {
+        read_persistent(&e, &DataKey::ClaimSettlement(project_id, objective_id))
+    }
+
+    pub fn get_holdback(e: Env, project_id: u128, objective_id: u128) -> Option<Holdback> {
+        read_holdback(&e, project_id, objective_id)
+    }
+
+    pub fn get_pending_holdbacks(e: Env, project_id: u128) -> Map<u128, Holdback> {
+        let (project, _) = get_project(&e, project_id);
+        pending_holdbacks(&e, project_id, &project)
+    }
+    
+    pub fn create_retainer(e: Env, freelancer: Address, amount: u128, period: u64, token: Address, user: Address) -> u128 {
+        user.require_auth();
+
+        if amount == 0 {
+            panic!("Amount must be greater than zero");
+        }
+
+        if period == 0 {
+            panic!("Period must be greater than zero");
+        }
+
+        require_registered_parties(&e, &user, &freelancer);
+
+        let project_count = next_project_id(&e);
+
+        // The single objective repeats every period for the same amount
+        let mut objectives: Map<u128, Objective> = Map::new(&e);
+        objectives.set(0, Objective {
+            price: amount,
+            half_paid: 0,
+            completed: false,
+            kind: ObjectiveKind::Milestone,
+        });
+
+        let project = Project {
+            client: user.clone(),
+            freelancer: freelancer.clone(),
+            token,
+            objectives_count: 1,
+            objectives,
+            completed_objectives: 0,
+            earned_amount: 0,
+            contract_balance: 0,
+            cancelled: false,
+            completed: false,
+            terminated: false,
+            withdrawn: false,
+            kind: ProjectKind::Retainer(Retainer {
+                amount,
+                period,
+                next_charge: e.ledger().timestamp() + period,
+                periods_charged: 0,
+                paused: false,
+            }),
+            terms: ProjectTerms::default(),
+        };
+
+        let project_key = DataKey::Project(project_count);
+        e.storage().instance().set(&project_key, &project);
+        add_address_project(&e, &project.client, project_count);
+        add_address_project(&e, &project.freelancer, project_count);
+
+        project_created(&e, project_count, &project, Vec::from_array(&e, [amount]));
+
+        project_count
+    }
+
+    pub fn charge_period(e: Env, project_id: u128) {
+        let (mut project, project_key) = get_project(&e, project_id);
+
+        let mut retainer = match project.kind.clone() {
+            ProjectKind::Retainer(retainer) => retainer,
+            _ => panic!("Project is not a retainer"),
+        };
+
+        if project.completed {
+            panic!("Project is completed");
+        }
+
+        if project.cancelled {
+            panic!("Project is cancelled");
+        }
+
+        if retainer.paused {
+            panic!("Retainer is paused");
+        }
+
+        if e.ledger().timestamp() < retainer.next_charge {
+            panic!("Period has not elapsed yet");
+        }
+
+        let amount = retainer.amount;
+        pull_from_client(&e, &project.token, &project.client, amount);
+        credit(&e, &project.freelancer, project_id, &project.token, amount);
+
+        retainer.periods_charged += 1;
+        retainer.next_charge += retainer.period;
+        project.earned_amount += retainer.amount;
+
+        let period = retainer.periods_charged;
+        project.kind = ProjectKind::Retainer(retainer);
+        e.storage().instance().set(&project_key, &project);
+
+        retainer_charged(&e, project_id, &project, period, amount);
+    }
+
+    pub fn pause_retainer(e: Env, project_id: u128) {
+        let (mut project, project_key) = get_project(&e, project_id);
+        project.client.require_auth();
+
+        let mut retainer = match project.kind.clone() {
+            ProjectKind::Retainer(retainer) => retainer,
+            _ => panic!("Project is not a retainer"),
+        };
+
+        if project.cancelled {
+            panic!("Project is cancelled");
+        }
+
+        if retainer.paused {
+            panic!("Retainer is already paused");
+        }
+
+        retainer.paused = true;
+        let next_charge = retainer.next_charge;
+        project.kind = ProjectKind::Retainer(retainer);
+        e.storage().instance().set(&project_key, &project);
+
+        retainer_paused(&e, project_id, &project, next_charge);
+    }
+
+    pub fn resume_retainer(e: Env, project_id: u128) {
+        let (mut project, project_key) = get_project(&e, project_id);
+        project.client.require_auth();
+
+        let mut retainer = match project.kind.clone() {
+            ProjectKind::Retainer(retainer) => retainer,
+            _ => panic!("Project is not a retainer"),
+        };
+
+        if project.cancelled {
+            panic!("Project is cancelled");
+        }
+
+        if !retainer.paused {
+            panic!("Retainer is not paused");
+        }
+
+        // The time spent paused is not billed, a new period starts now
+        let next_charge = e.ledger().timestamp() + retainer.period;
+        retainer.paused = false;
+        retainer.next_charge = next_charge;
+        project.kind = ProjectKind::Retainer(retainer);
+        e.storage().instance().set(&project_key, &project);
+
+        retainer_resumed(&e, project_id, &project, next_charge);
+    }
+
+    pub fn create_hourly_project(e: Env, freelancer: Address, rate: u128, weekly_cap: u32, token: Address, user: Address) -> u128 {
+        user.require_auth();
+
+        if rate == 0 {
+            panic!("Rate must be greater than zero");
+        }
+
+        if weekly_cap == 0 {
+            panic!("Weekly cap must be greater than zero");
+        }
+
+        require_registered_parties(&e, &user, &freelancer);
+
+        let project_count = next_project_id(&e);
+
+        let project = Project {
+            client: user.clone(),
+            freelancer: freelancer.clone(),
+            token,
+            objectives_count: 0,
+            objectives: Map::new(&e),
+            completed_objectives: 0,
+            earned_amount: 0,
+            contract_balance: 0,
+            cancelled: false,
+            completed: false,
+            terminated: false,
+            withdrawn: false,
+            kind: ProjectKind::Hourly(HourlyTerms {
+                rate,
+                weekly_cap,
+                start_time: e.ledger().timestamp(),
+            }),
+            terms: ProjectTerms::default(),
+        };
+
+        let project_key = DataKey::Project(project_count);
+        e.storage().instance().set(&project_key, &project);
+        add_address_project(&e, &project.client, project_count);
+        add_address_project(&e, &project.freelancer, project_count);
+
+        project_created(&e, project_count, &project, Vec::new(&e));
+
+        project_count
+    }
+
+    pub fn fund_hourly(e: Env, project_id: u128, amount: u128) {
+        let (mut project, project_key) = get_project(&e, project_id);
+        project.client.require_auth();
+
+        hourly_terms(&project);
+
+        if project.completed {
+            panic!("Project is completed");
+        }
+
+        if project.cancelled {
+            panic!("Project is cancelled");
+        }
+
+        pull_from_client(&e, &project.token, &project.client, amount);
+
+        project.contract_balance += amount;
+        e.storage().instance().set(&project_key, &project);
+
+        hourly_funded(&e, project_id, &project, amount);
+    }
+
+    pub fn submit_time_entry(e: Env, project_id: u128, week: u32, hours: u32, description_hash: BytesN<32>, user: Address) {
+        let (project, _) = get_project(&e, project_id);
+        require_party(&e, &project.freelancer, &user);
+
+        let terms = hourly_terms(&project);
+
+        if project.completed {
+            panic!("Project is completed");
+        }
+
+        if project.cancelled {
+            panic!("Project is cancelled");
+        }
+
+        if hours == 0 {
+            panic!("Hours must be greater than zero");
+        }
+
+        let now = e.ledger().timestamp();
+        let current_week = ((now - terms.start_time) / WEEK_IN_SECONDS) as u32;
+        if week > current_week {
+            panic!("Cannot log time for a future week");
+        }
+
+        let (mut timesheet, timesheet_key) = get_timesheet(&e, project_id, week);
+
+        if timesheet.status != TimesheetStatus::Pending {
+            panic!("Week is already approved or disputed");
+        }
+
+        if timesheet.hours + hours > terms.weekly_cap {
+            panic!("Weekly hour cap exceeded");
+        }
+
+        timesheet.entries.push_back(TimeEntry {
+            hours,
+            description_hash: description_hash.clone(),
+            timestamp: now,
+        });
+        timesheet.hours += hours;
+        write_persistent(&e, &timesheet_key, &timesheet);
+
+        time_logged(&e, project_id, &project, week, hours, description_hash);
+    }
+
+    pub fn approve_week(e: Env, project_id: u128, week: u32, user: Address) -> u128 {
+        let (mut project, project_key) = get_project(&e, project_id);
+        require_client_or_delegate(&e, project_id, &project, &user, Permission::ReviewTimesheets);
+
+        let terms = hourly_terms(&project);
+        let (mut timesheet, timesheet_key) = get_timesheet(&e, project_id, week);
+
+        if timesheet.hours == 0 {
+            panic!("No time logged for this week");
+        }
+
+        // A disputed week can still be approved once the parties settle it
+        if timesheet.status == TimesheetStatus::Approved {
+            panic!("Week already approved");
+        }
+
+        let amount = terms.rate * timesheet.hours as u128;
+        if project.contract_balance < amount {
+            panic!("Not enough funds in escrow to pay this week");
+        }
+
+        credit(&e, &project.freelancer, project_id, &project.token, amount);
+
+        project.contract_balance -= amount;
+        project.earned_amount += amount;
+        timesheet.status = TimesheetStatus::Approved;
+
+        write_persistent(&e, &timesheet_key, &timesheet);
+        e.storage().instance().set(&project_key, &project);
+
+        week_approved(&e, project_id, &project, week, user, amount);
+
+        amount
+    }
+
+    pub fn dispute_week(e: Env, project_id: u128, week: u32, user: Address) {
+        let (project, _) = get_project(&e, project_id);
+        require_client_or_delegate(&e, project_id, &project, &user, Permission::ReviewTimesheets);
+
+        hourly_terms(&project);
+        let (mut timesheet, timesheet_key) = get_timesheet(&e, project_id, week);
+
+        if timesheet.hours == 0 {
+            panic!("No time logged for this week");
+        }
+
+        if timesheet.status != TimesheetStatus::Pending {
+            panic!("Week is already approved or disputed");
+        }
+
+        timesheet.status = TimesheetStatus::Disputed;
+        write_persistent(&e, &timesheet_key, &timesheet);
+
+        week_disputed(&e, project_id, &project, week, user);
+    }
+
+    pub fn get_timesheet(e: Env, project_id: u128, week: u32) -> Timesheet {
+        get_timesheet(&e, project_id, week).0
+    }
+
+    pub fn rate_project(e: Env, project_id: u128, score: u32, comment_hash: BytesN<32>, user: Address) {
+        user.require_auth();
+        let (project, _) = get_project(&e, project_id);
+
+        let ratee = if user == project.client {
+            project.freelancer.clone()
+        } else if user == project.freelancer {
+            project.client.clone()
+        } else {
+            panic!("Only the client or the freelancer can rate the project");
+        };
+
+        // A cancelled project counts as a disputed outcome and can be rated too
+        if !project.completed && !project.cancelled {
+            panic!("Project must be completed or cancelled to be rated");
+        }
+
+        // Anyone can name any freelancer on a project, so only work that was
+        // actually paid for can be rated, escrow that was refunded costs nothing
+        if project.earned_amount == 0 {
+            panic!("Only projects that paid the freelancer can be rated");
+        }
+
+        if !(MIN_RATING..=MAX_RATING).contains(&score) {
+            panic!("Rating must be between 1 and 5");
+        }
+
+        let rating_key = DataKey::Rating(project_id, user.clone());
+        if e.storage().persistent().has(&rating_key) {
+            panic!("Project already rated by this user");
+        }
+
+        let rating = Rating {
+            rater: user.clone(),
+            ratee: ratee.clone(),
+            score,
+            comment_hash: comment_hash.clone(),
+            timestamp: e.ledger().timestamp(),
+        };
+        write_persistent(&e, &rating_key, &rating);
+
+        record_rating(&e, &ratee, score);
+
+        project_rated(&e, project_id, &project, user, ratee, score, comment_hash);
+    }
+
+    pub fn get_rating(e: Env, project_id: u128, rater: Address) -> Option<Rating> {
+        read_persistent(&e, &DataKey::Rating(project_id, rater))
+    }
+
+    pub fn get_reputation(e: Env, user: Address) -> Reputation {
+        read_reputation(&e, &user)
+    }
+
+    pub fn get_project(e: Env, project_id: u128) -> Project {
+        get_project(&e, project_id).0
+    }
+
+    pub fn get_objective(e: Env, project_id: u128, objective_id: u128) -> Objective {
+        let (project, _) = get_project(&e, project_id);
+
+        match project.objectives.get(objective_id) {
+            Some(objective) => objective,
+            None => panic!("Objective not found"),
+        }
+    }
+
+    pub fn get_project_summary(e: Env, project_id: u128) -> ProjectSummary {
+        let (project, _) = get_project(&e, project_id);
+        project_summary(&e, project_id, &project)
+    }
+
+    pub fn withdraw(e: Env, user: Address, token: Address) -> u128 {
+        Self::withdraw_to(e, user.clone(), token, user)
+    }
+
+    pub fn withdraw_to(e: Env, user: Address, token: Address, destination: Address) -> u128 {
+        user.require_auth();
+
+        let amount = take_claimable(&e, &user, &token);
+        if amount == 0 {
+            panic!("Nothing to withdraw");
+        }
+
+        let usdc_client = TokenClient::new(&e, &token);
+        usdc_client.transfer(
+            &e.current_contract_address(),
+            &destination,
+            &(amount as i128)
+        );
+
+        funds_withdrawn(&e, user, token, destination, amount);
+
+        amount
+    }
+
+    pub fn claimable(e: Env, user: Address) -> Map<Address, u128> {
+        read_claimable(&e, &user)
+    }
+
+    pub fn get_project_with_profiles(e: Env, project_id: u128) -> (Project, Option<User>, Option<User>) {
+        let (project, _) = get_project(&e, project_id);
+
+        let client_profile = read_user(&e, &project.client);
+        let freelancer_profile = read_user(&e, &project.freelancer);
+
+        (project, client_profile, freelancer_profile)
+    }
+
+    pub fn get_projects_by_freelancer(e: Env, freelancer: Address) -> Vec<Project> {
+        let all_projects: Vec<Project> = get_all_projects(e.clone());
+    
+        let mut result: Vec<Project> = Vec::new(&e);
+    
+        for project in all_projects.iter() {
+            if project.freelancer == freelancer {
+                result.push_back(project);
+            }
+        }
+    
+        result
+    }
+
+    pub fn get_projects_by_client(e: Env, client: Address) -> Vec<Project> {
+        let all_projects: Vec<Project> = get_all_projects(e.clone());
+    
+        let mut result: Vec<Project> = Vec::new(&e);
+    
+        for project in all_projects.iter() {
+            if project.client == client {
+                result.push_back(project);
+            }
+        }
+    
+        result
+    }
+      
+    #[allow(clippy::needless_return)]
+    pub fn register(e: Env, user_address: Address, handle: String, profile: UserProfile) -> bool {
+        user_address.require_auth();
+
+        let key = DataKey::User(user_address.clone());
+
+        if e.storage().persistent().has(&key) {
+            return false;
+        }
+
+        if e.storage().persistent().has(&DataKey::BlockedUser(user_address.clone())) {
+            panic!("This address is not allowed to register again");
+        }
+
+        let handle = normalize_handle(&e, &handle);
+        let handle_key = DataKey::Handle(handle.clone());
+        if e.storage().persistent().has(&handle_key) {
+            panic!("Handle already taken");
+        }
+
+        let user_id = read_persistent(&e, &DataKey::UserCounter)
+            .unwrap_or(0)
             + 1;
 
-        e.storage()
-            .persistent()
-            .set(&DataKey::UserCounter, &user_id);
+        write_persistent(&e, &DataKey::UserCounter, &user_id);
+
+        let user = User {
+            id: user_id,
+            user: user_address.clone(),
+            handle,
+            profile,
+            registered: true,
+            timestamp: e.ledger().timestamp(),
+        };
+
+        write_persistent(&e, &DataKey::User(user_address.clone()), &user);
+
+        write_persistent(&e, &handle_key, &user_address);
+
+        write_persistent(&e, &DataKey::UserId(user_id), &user_address);
+
+        let user_reg_id = e.ledger().sequence();
+
+        write_persistent(&e, &DataKey::UserRegId(user_address.clone()), &user_reg_id);
+
+        user_registered(&e, user_address, user_id);
+
+        return true;
+    }
+
+    pub fn update_profile(e: Env, user_address: Address, profile: UserProfile) {
+        user_address.require_auth();
+
+        let key = DataKey::User(user_address.clone());
+        let mut user: User = match read_persistent(&e, &key) {
+            Some(user) => user,
+            None => panic!("User not found"),
+        };
 
-        let user = User {
-            id: user_id,
-            user: user_address.clone(),
-            name: name.clone(),
-            email: email.clone(),
-            registered: true,
-            timestamp: e.ledger().timestamp(),
+        user.profile = profile;
+
+        write_persistent(&e, &key, &user);
+
+        user_updated(&e, user_address, user.id);
+    }
+
+    pub fn delete_profile(e: Env, user_address: Address, block_reregistration: bool) {
+        user_address.require_auth();
+
+        let user = match read_user(&e, &user_address) {
+            Some(user) => user,
+            None => panic!("User not found"),
         };
 
         e.storage()
             .persistent()
-            .set(&DataKey::User(user_address.clone()), &user);
-
-        let user_reg_id = e.ledger().sequence();
-
+            .remove(&DataKey::User(user_address.clone()));
+        e.storage()
+            .persistent()
+            .remove(&DataKey::UserRegId(user_address.clone()));
         e.storage()
             .persistent()
-            .set(&DataKey::UserRegId(user_address.clone()), &user_reg_id);
+            .remove(&DataKey::UserId(user.id));
 
-        return true;
+        // A blocked address keeps its handle reserved so nobody can take it over
+        if block_reregistration {
+            write_persistent(&e, &DataKey::BlockedUser(user_address.clone()), &true);
+        } else {
+            e.storage()
+                .persistent()
+                .remove(&DataKey::Handle(user.handle));
+        }
+
+        user_deleted(&e, user_address, user.id, block_reregistration);
     }
 
-    pub fn login(e: Env, user_address: Address) -> String {
+    pub fn set_recovery(e: Env, user_address: Address, guardians: Vec<Address>, threshold: u32) {
         user_address.require_auth();
-    
-        let key = DataKey::User(user_address.clone());
-    
-        if let Some(user) = e.storage().persistent().get::<_, User>(&key) {
-            user.name
-        } else {
-            soroban_sdk::String::from_str(&e, "User not found")
+
+        if read_user(&e, &user_address).is_none() {
+            panic!("User not found");
+        }
+
+        if threshold == 0 || threshold > guardians.len() {
+            panic!("Threshold must be between 1 and the number of guardians");
+        }
+
+        if guardians.contains(&user_address) {
+            panic!("A user cannot be their own guardian");
+        }
+
+        let guardian_count = guardians.len();
+        write_persistent(&e, &DataKey::Recovery(user_address.clone()), &RecoveryConfig { guardians, threshold });
+
+        // Pending approvals were given under the previous guardian set
+        clear_recovery_requests(&e, &user_address);
+
+        recovery_set(&e, user_address, guardian_count, threshold);
+    }
+
+    pub fn get_recovery(e: Env, user_address: Address) -> Option<RecoveryConfig> {
+        read_persistent(&e, &DataKey::Recovery(user_address))
+    }
+
+    pub fn approve_recovery(e: Env, lost_address: Address, new_address: Address, guardian: Address) -> bool {
+        guardian.require_auth();
+
+        let recovery: RecoveryConfig = match read_persistent(&e, &DataKey::Recovery(lost_address.clone())) {
+            Some(recovery) => recovery,
+            None => panic!("No recovery configured for this user"),
+        };
+
+        if !recovery.guardians.contains(&guardian) {
+            panic!("Only a guardian can approve a recovery");
+        }
+
+        let approvals = approve_recovery_candidate(&e, &lost_address, &new_address, &guardian);
+
+        recovery_approved(&e, lost_address.clone(), new_address.clone(), guardian, approvals);
+
+        if approvals < recovery.threshold {
+            return false;
+        }
+
+        let projects = migrate_user(&e, &lost_address, &new_address);
+        user_migrated(&e, lost_address, new_address, projects);
+
+        true
+    }
+
+    pub fn migrate_account(e: Env, old_address: Address, new_address: Address) {
+        old_address.require_auth();
+        new_address.require_auth();
+
+        let projects = migrate_user(&e, &old_address, &new_address);
+        user_migrated(&e, old_address, new_address, projects);
+    }
+
+    pub fn set_delegate(e: Env, project_id: u128, delegate: Address, permissions: DelegatePermissions) {
+        let (project, _) = get_project(&e, project_id);
+        project.client.require_auth();
+
+        if delegate == project.client || delegate == project.freelancer {
+            panic!("Project parties cannot be delegates");
+        }
+
+        write_delegate(&e, project_id, &delegate, &permissions);
+
+        delegate_set(&e, project_id, &project, delegate, permissions);
+    }
+
+    pub fn remove_delegate(e: Env, project_id: u128, delegate: Address) {
+        let (project, _) = get_project(&e, project_id);
+        project.client.require_auth();
+
+        if read_delegate(&e, project_id, &delegate).is_none() {
+            panic!("Not a delegate of this project");
+        }
+
+        remove_delegate(&e, project_id, &delegate);
+
+        delegate_removed(&e, project_id, &project, delegate);
+    }
+
+    pub fn get_delegate(e: Env, project_id: u128, delegate: Address) -> Option<DelegatePermissions> {
+        read_delegate(&e, project_id, &delegate)
+    }
+
+    pub fn create_organization(e: Env, account: Address, members: Map<Address, OrgRole>, threshold: u32) -> u64 {
+        account.require_auth();
+
+        if read_organization_by_account(&e, &account).is_some() {
+            panic!("Account already belongs to an organization");
+        }
+
+        let org_id: u64 = read_persistent(&e, &DataKey::OrgCounter)
+            .unwrap_or(0)
+            + 1;
+
+        write_persistent(&e, &DataKey::OrgCounter, &org_id);
+
+        let org = Organization {
+            id: org_id,
+            account: account.clone(),
+            members,
+            threshold,
+        };
+
+        if org.members.values().iter().all(|role| role != OrgRole::Owner) {
+            panic!("An organization needs at least one owner");
+        }
+
+        if threshold == 0 || threshold > approver_count(&org) {
+            panic!("Threshold must be between 1 and the number of approvers");
+        }
+
+        write_organization(&e, &org);
+        write_persistent(&e, &DataKey::OrgAccount(account.clone()), &org_id);
+        for member in org.members.keys().iter() {
+            add_member_org(&e, &member, org_id);
+        }
+
+        org_created(&e, org_id, account, org.members.len(), org.threshold);
+
+        org_id
+    }
+
+    pub fn set_org_member(e: Env, org_id: u64, member: Address, role: OrgRole, caller: Address) {
+        let mut org = read_organization(&e, org_id);
+        require_org_owner(&org, &caller);
+
+        org.members.set(member.clone(), role);
+
+        if org.members.values().iter().all(|role| role != OrgRole::Owner) {
+            panic!("An organization needs at least one owner");
+        }
+
+        if approver_count(&org) < org.threshold {
+            panic!("Not enough approvers left for the threshold");
+        }
+
+        write_organization(&e, &org);
+        add_member_org(&e, &member, org_id);
+        org_updated(&e, org_id, org.account.clone(), org.members.len(), org.threshold);
+    }
+
+    pub fn remove_org_member(e: Env, org_id: u64, member: Address, caller: Address) {
+        let mut org = read_organization(&e, org_id);
+        require_org_owner(&org, &caller);
+
+        if org.members.remove(member).is_none() {
+            panic!("Not a member of the organization");
+        }
+
+        if org.members.values().iter().all(|role| role != OrgRole::Owner) {
+            panic!("An organization needs at least one owner");
+        }
+
+        if approver_count(&org) < org.threshold {
+            panic!("Not enough approvers left for the threshold");
+        }
+
+        write_organization(&e, &org);
+        org_updated(&e, org_id, org.account.clone(), org.members.len(), org.threshold);
+    }
+
+    pub fn set_org_threshold(e: Env, org_id: u64, threshold: u32, caller: Address) {
+        let mut org = read_organization(&e, org_id);
+        require_org_owner(&org, &caller);
+
+        if threshold == 0 || threshold > approver_count(&org) {
+            panic!("Threshold must be between 1 and the number of approvers");
+        }
+
+        org.threshold = threshold;
+        write_organization(&e, &org);
+        org_updated(&e, org_id, org.account.clone(), org.members.len(), org.threshold);
+    }
+
+    pub fn get_organization(e: Env, org_id: u64) -> Organization {
+        read_organization(&e, org_id)
+    }
+
+    pub fn get_organization_by_account(e: Env, account: Address) -> Option<Organization> {
+        read_organization_by_account(&e, &account)
+    }
+
+    pub fn approve_org_action(e: Env, project_id: u128, action: OrgAction, member: Address) -> bool {
+        member.require_auth();
+        let (project, _) = get_project(&e, project_id);
+
+        let org = match read_organization_by_account(&e, &project.client) {
+            Some(org) => org,
+            None => panic!("Project client is not an organization"),
+        };
+
+        if !is_org_approver(&org, &member) {
+            panic!("Only organization owners and approvers can approve");
+        }
+
+        let approvals_key = DataKey::OrgApprovals(project_id, action.clone());
+        let mut approvals: Vec<Address> = read_persistent(&e, &approvals_key)
+            .unwrap_or(Vec::new(&e));
+
+        if approvals.contains(&member) {
+            panic!("Member already approved this action");
+        }
+
+        // Approvals from members who were removed or demoted no longer count
+        let mut valid_approvals: Vec<Address> = Vec::new(&e);
+        for approver in approvals.iter() {
+            if is_org_approver(&org, &approver) {
+                valid_approvals.push_back(approver);
+            }
+        }
+        approvals = valid_approvals;
+        approvals.push_back(member.clone());
+
+        org_action_approved(&e, project_id, &project, member, action.clone(), approvals.len(), org.threshold);
+
+        if approvals.len() < org.threshold {
+            write_persistent(&e, &approvals_key, &approvals);
+            return false;
         }
+
+        e.storage().persistent().remove(&approvals_key);
+
+        match action {
+            OrgAction::FundObjective(objective_id) => fund_objectives_from_client(&e, project_id, &Vec::from_array(&e, [objective_id])),
+            OrgAction::CompleteObjective(objective_id) => complete_objectives_from_client(&e, project_id, &Vec::from_array(&e, [objective_id])),
+        }
+
+        true
+    }
+
+    pub fn get_user(e: Env, user_address: Address) -> Option<User> {
+        read_user(&e, &user_address)
+    }
+
+    pub fn get_user_by_handle(e: Env, handle: String) -> Option<User> {
+        let handle = normalize_handle(&e, &handle);
+        let user_address: Address = read_persistent(&e, &DataKey::Handle(handle))?;
+        read_user(&e, &user_address)
+    }
+
+    pub fn get_user_by_id(e: Env, user_id: u64) -> Option<User> {
+        read_user_by_id(&e, user_id)
+    }
+
+    pub fn list_users(e: Env, role: Option<UserRole>, start_id: u64, limit: u32) -> Vec<User> {
+        let user_count: u64 = read_persistent(&e, &DataKey::UserCounter)
+            .unwrap_or(0);
+
+        let mut users: Vec<User> = Vec::new(&e);
+
+        for id in start_id.max(1)..=user_count {
+            if users.len() >= limit {
+                break;
+            }
+
+            if let Some(user) = read_user_by_id(&e, id) {
+                if role.as_ref().is_none_or(|role| has_role(&user, role)) {
+                    users.push_back(user);
+                }
+            }
+        }
+
+        users
+    }
+
+    pub fn is_registered(e: Env, user_address: Address) -> bool {
+        read_user(&e, &user_address).is_some()
+    }
+
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn create_milestone_project(e: &Env, client: &Address, freelancer: &Address, token: &Address, prices: Vec<u128>, terms: ProjectTerms) -> u128 {
+    if prices.is_empty() {
+        panic!("Prices cannot be empty");
+    }
+
+    require_valid_terms(&terms);
+
+    require_registered_parties(e, client, freelancer);
+
+    let project_count = next_project_id(e);
+    let mut objectives: Map<u128, Objective> = Map::new(e);
+    for (i, price) in prices.iter().enumerate() {
+        objectives.set(i as u128, Objective {
+            price: price as u128,
+            half_paid: 0,
+            completed: false,
+            kind: ObjectiveKind::Milestone,
+        });
+    }
+
+    let project = Project {
+        client: client.clone(),
+        freelancer: freelancer.clone(),
+        token: token.clone(),
+        objectives_count: prices.len() as u128,
+        objectives,
+        completed_objectives: 0,
+        earned_amount: 0,
+        contract_balance: 0,
+        cancelled: false,
+        completed: false,
+        terminated: false,
+        withdrawn: false,
+        kind: ProjectKind::Milestones,
+        terms,
+    };
+
+    let project_key = DataKey::Project(project_count);
+    e.storage().instance().set(&project_key, &project);
+    add_address_project(e, &project.client, project_count);
+    add_address_project(e, &project.freelancer, project_count);
+
+    project_created(e, project_count, &project, prices);
+
+    project_count
+}
+
+// Returns every escrowed amount the freelancer has not earned yet to whoever funded it
+fn refund_uncompleted_escrow(e: &Env, project_id: u128, project: &mut Project) -> u128 {
+    let now = e.ledger().timestamp();
+    let mut refunded: u128 = 0;
+    let mut to_client: u128 = 0;
+    for (objective_id, mut objective) in project.objectives.clone().iter() {
+        let amount = refundable_amount(&objective, now);
+
+        // Streams stop vesting once their unvested escrow is returned
+        if let ObjectiveKind::Stream(mut stream) = objective.kind.clone() {
+            if stream.cancelled_at.is_none() {
+                stream.cancelled_at = Some(now);
+                objective.kind = ObjectiveKind::Stream(stream);
+            }
+        }
+
+        objective.half_paid -= amount;
+        project.objectives.set(objective_id, objective);
+
+        // Contributions to an objective that never reached its price go back as well
+        let amount = amount + raised_amount(e, project_id, objective_id);
+        remove_crowdfund(e, project_id, objective_id);
+        refunded += amount;
+
+        if amount > 0 {
+            to_client += refund_objective(e, project_id, project, objective_id, amount);
+            objective_refunded(e, project_id, project, objective_id, amount);
+        }
+    }
+
+    // Hourly projects keep their escrow in a single balance
+    refunded += project.contract_balance;
+    to_client += project.contract_balance;
+    project.contract_balance = 0;
+
+    // One credit and one event for the client's total
+    if to_client > 0 {
+        credit(e, &project.client, project_id, &project.token, to_client);
+        project_refunded(e, project_id, project, project.client.clone(), to_client);
+    }
+
+    refunded
+}
+
+// Contributions are only kept for refunds, objectives with a pending holdback still need theirs
+fn clear_contributions(e: &Env, project_id: u128, project: &Project) {
+    for (objective_id, _) in project.objectives.iter() {
+        if read_holdback(e, project_id, objective_id).is_none() {
+            remove_contributions(e, project_id, objective_id);
+        }
+    }
+}
+
+// Credits third-party funders their share of an objective refund and returns what is left for the client
+fn refund_objective(e: &Env, project_id: u128, project: &Project, objective_id: u128, amount: u128) -> u128 {
+    let contributions = read_contributions(e, project_id, objective_id);
+    if contributions.is_empty() {
+        return amount;
+    }
+
+    for (funder, share) in refund_shares(e, &contributions, amount).iter() {
+        credit(e, &funder, project_id, &project.token, share);
+        project_refunded(e, project_id, project, funder, share);
+    }
+
+    0
+}
+
+fn require_valid_terms(terms: &ProjectTerms) {
+    if terms.kill_fee_percent > 100 {
+        panic!("Kill fee must be a percentage between 0 and 100");
+    }
+
+    if terms.holdback_percent > 100 {
+        panic!("Holdback must be a percentage between 0 and 100");
+    }
+
+    if terms.holdback_percent > 0 && terms.holdback_days == 0 {
+        panic!("Holdback needs a period of at least one day");
+    }
+}
+
+fn require_registered_parties(e: &Env, client: &Address, freelancer: &Address) {
+    if !read_require_registration(e) {
+        return;
     }
 
-}
\ No newline at end of file
+    if !e.storage().persistent().has(&DataKey::User(client.clone())) {
+        panic!("Client is not registered");
+    }
+
+    if !e.storage().persistent().has(&DataKey::User(freelancer.clone())) {
+        panic!("Freelancer is not registered");
+    }
+}
+
+fn require_not_crowdfunded(e: &Env, project_id: u128, objective_id: u128) {
+    if read_crowdfund(e, project_id, objective_id).is_some() {
+        panic!("Objective is funded through contributions");
+    }
+}
+
+fn require_not_org_client(e: &Env, project: &Project) {
+    if read_organization_by_account(e, &project.client).is_some() {
+        panic!("Organization clients fund and complete objectives through approve_org_action");
+    }
+}
+
+// The organization account pre-approves the escrow as spender, so funds are
+// pulled with transfer_from once enough members have approved.
+fn fund_objectives_from_client(e: &Env, project_id: u128, objective_ids: &Vec<u128>) {
+    let (mut project, project_key) = get_project(e, project_id);
+    require_milestones(&project);
+
+    if project.completed {
+        panic!("Project is completed");
+    }
+
+    if project.cancelled {
+        panic!("Project is cancelled");
+    }
+
+    if objective_ids.is_empty() {
+        panic!("No objectives given");
+    }
+
+    // Everything is validated before a single transfer covers the whole batch
+    let mut amounts: Vec<u128> = Vec::new(e);
+    let mut total: u128 = 0;
+    for objective_id in objective_ids.iter() {
+        require_not_crowdfunded(e, project_id, objective_id);
+        let amount = escrow_objective(&mut project, objective_id);
+        amounts.push_back(amount);
+        total += amount;
+    }
+
+    pull_from_client(e, &project.token, &project.client, total);
+    e.storage().instance().set(&project_key, &project);
+
+    for (objective_id, amount) in objective_ids.iter().zip(amounts.iter()) {
+        objective_funded(e, project_id, &project, objective_id, amount);
+    }
+}
+
+fn complete_objectives_from_client(e: &Env, project_id: u128, objective_ids: &Vec<u128>) {
+    let (mut project, project_key) = get_project(e, project_id);
+    require_milestones(&project);
+
+    if objective_ids.is_empty() {
+        panic!("No objectives given");
+    }
+
+    let release_at = e.ledger().timestamp() + project.terms.holdback_days as u64 * DAY_IN_SECONDS;
+
+    let mut payouts: Vec<u128> = Vec::new(e);
+    let mut holdbacks: Vec<u128> = Vec::new(e);
+    let mut remaining: u128 = 0;
+    let mut total: u128 = 0;
+    for objective_id in objective_ids.iter() {
+        let objective = release_objective(&mut project, objective_id);
+        remaining += objective.price - objective.half_paid;
+
+        // The client pays the rest of a sponsored objective, holdback refunds are split with it
+        if objective.price > objective.half_paid && !read_contributions(e, project_id, objective_id).is_empty() {
+            add_contribution(e, project_id, objective_id, &project.client, objective.price - objective.half_paid);
+        }
+
+        // The warranty share stays in escrow until the holdback period is over
+        let held = objective.price * project.terms.holdback_percent as u128 / 100;
+        if held > 0 {
+            write_holdback(e, project_id, objective_id, &Holdback {
+                amount: held,
+                release_at,
+                claim_hash: BytesN::from_array(e, &[0; 32]),
+                claimed: false,
+            });
+        }
+
+        let payout = objective.price - held;
+        total += payout;
+        payouts.push_back(payout);
+        holdbacks.push_back(held);
+    }
+
+    project.earned_amount += total;
+
+    pull_from_client(e, &project.token, &project.client, remaining);
+    credit(e, &project.freelancer, project_id, &project.token, total);
+    e.storage().instance().set(&project_key, &project);
+
+    for (i, objective_id) in objective_ids.iter().enumerate() {
+        objective_completed(e, project_id, &project, objective_id, payouts.get(i as u32).unwrap());
+
+        let held = holdbacks.get(i as u32).unwrap();
+        if held > 0 {
+            holdback_started(e, project_id, &project, objective_id, held, release_at);
+        }
+    }
+}
+
+// Marks the objective as funded and returns the amount to escrow for it
+fn escrow_objective(project: &mut Project, objective_id: u128) -> u128 {
+    let mut objective = match project.objectives.get(objective_id) {
+        Some(objective) => objective,
+        None => panic!("Objective not found"),
+    };
+
+    if objective.half_paid > 0 {
+        panic!("Objective already funded");
+    }
+
+    if objective.completed {
+        panic!("Objective already completed");
+    }
+
+    // Streams escrow the full price up front since it vests continuously
+    let amount = match objective.kind {
+        ObjectiveKind::Stream(_) => objective.price,
+        ObjectiveKind::Milestone => objective.price / 2,
+    };
+
+    objective.half_paid = amount;
+    project.objectives.set(objective_id, objective);
+
+    amount
+}
+
+// Marks a funded milestone as completed and returns it as it was before
+fn release_objective(project: &mut Project, objective_id: u128) -> Objective {
+    let objective = match project.objectives.get(objective_id) {
+        Some(objective) => objective,
+        None => panic!("Objective not found"),
+    };
+
+    if let ObjectiveKind::Stream(_) = objective.kind {
+        panic!("Streaming objectives are paid through withdraw_vested");
+    }
+
+    if objective.half_paid == 0 {
+        panic!("Objective not funded");
+    }
+
+    if objective.completed {
+        panic!("Objective already completed");
+    }
+
+    let mut completed = objective.clone();
+    completed.completed = true;
+    project.objectives.set(objective_id, completed);
+    project.completed_objectives += 1;
+
+    objective
+}
+
+// Client funds only enter escrow through the allowance given to this contract
+fn pull_from_client(e: &Env, usdc_contract: &Address, client: &Address, amount: u128) {
+    if amount == 0 {
+        return;
+    }
+
+    let contract_address = e.current_contract_address();
+    let usdc_client = TokenClient::new(e, usdc_contract);
+
+    let allowance = usdc_client.allowance(client, &contract_address);
+    if allowance < amount as i128 {
+        panic!("Not enough allowance. Please approve the amount first.");
+    }
+
+    usdc_client.transfer_from(&contract_address, client, &contract_address, &(amount as i128));
+}
+
+fn require_milestones(project: &Project) {
+    match project.kind {
+        ProjectKind::Milestones => {}
+        ProjectKind::Retainer(_) => panic!("Retainer periods are charged through charge_period"),
+        ProjectKind::Hourly(_) => panic!("Hourly projects are paid through approved timesheets"),
+    }
+}
+
+fn hourly_terms(project: &Project) -> HourlyTerms {
+    match project.kind.clone() {
+        ProjectKind::Hourly(terms) => terms,
+        _ => panic!("Project is not hourly"),
+    }
+}
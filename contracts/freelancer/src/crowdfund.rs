@@ -0,0 +1,24 @@
+use soroban_sdk::Env;
+
+use crate::storage::{read_persistent, write_persistent};
+use crate::storage_types::{Crowdfund, DataKey};
+
+pub fn read_crowdfund(e: &Env, project_id: u128, objective_id: u128) -> Option<Crowdfund> {
+    read_persistent(e, &DataKey::Crowdfund(project_id, objective_id))
+}
+
+pub fn write_crowdfund(e: &Env, project_id: u128, objective_id: u128, crowdfund: &Crowdfund) {
+    write_persistent(e, &DataKey::Crowdfund(project_id, objective_id), crowdfund);
+}
+
+pub fn remove_crowdfund(e: &Env, project_id: u128, objective_id: u128) {
+    e.storage().persistent().remove(&DataKey::Crowdfund(project_id, objective_id));
+}
+
+// Contributions held for an objective that has not reached its price yet
+pub fn raised_amount(e: &Env, project_id: u128, objective_id: u128) -> u128 {
+    match read_crowdfund(e, project_id, objective_id) {
+        Some(crowdfund) => crowdfund.raised,
+        None => 0,
+    }
+}
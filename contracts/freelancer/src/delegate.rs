@@ -0,0 +1,55 @@
+use soroban_sdk::{Address, Env};
+
+use crate::storage::{add_address_project, read_persistent, write_persistent};
+use crate::storage_types::{DataKey, DelegatePermissions, Project};
+
+pub enum Permission {
+    ApproveObjectives,
+    AddObjectives,
+    ReviewTimesheets,
+}
+
+pub fn read_delegate(e: &Env, project_id: u128, delegate: &Address) -> Option<DelegatePermissions> {
+    read_persistent(e, &DataKey::Delegate(project_id, delegate.clone()))
+}
+
+pub fn write_delegate(e: &Env, project_id: u128, delegate: &Address, permissions: &DelegatePermissions) {
+    write_persistent(e, &DataKey::Delegate(project_id, delegate.clone()), permissions);
+    add_address_project(e, delegate, project_id);
+}
+
+pub fn remove_delegate(e: &Env, project_id: u128, delegate: &Address) {
+    e.storage().persistent().remove(&DataKey::Delegate(project_id, delegate.clone()));
+}
+
+// Authenticates `actor` as the project client, or as a delegate the client
+// granted `permission` to. Delegates never get to fund, cancel or refund.
+pub fn require_client_or_delegate(e: &Env, project_id: u128, project: &Project, actor: &Address, permission: Permission) {
+    actor.require_auth();
+
+    if *actor == project.client {
+        return;
+    }
+
+    let permissions = match read_delegate(e, project_id, actor) {
+        Some(permissions) => permissions,
+        None => panic!("Only the client or a delegate can do this"),
+    };
+
+    let allowed = match permission {
+        Permission::ApproveObjectives => permissions.approve_objectives,
+        Permission::AddObjectives => permissions.add_objectives,
+        Permission::ReviewTimesheets => permissions.review_timesheets,
+    };
+
+    if !allowed {
+        panic!("Delegate is not allowed to do this");
+    }
+}
+
+pub fn move_delegate(e: &Env, project_id: u128, old: &Address, new: &Address) {
+    if let Some(permissions) = read_delegate(e, project_id, old) {
+        remove_delegate(e, project_id, old);
+        write_delegate(e, project_id, new, &permissions);
+    }
+}
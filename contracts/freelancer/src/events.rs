@@ -1,83 +1,660 @@
-use soroban_sdk::{Env, vec, IntoVal, Val, Address, Vec, symbol_short};
-use crate::storage_types::DataKey;
+use soroban_sdk::{contracttype, Address, BytesN, Env, IntoVal, Symbol, Val, Vec};
+use crate::storage_types::{DelegatePermissions, OrgAction, Project, ProjectKind, WithdrawalReason};
 
-// Event for project created
-pub (crate) fn project_created(e: &Env, project_id: DataKey, client: Address, freelancer: Address, prices: Vec<u128>) {
-    let topics = (symbol_short!("p_created"),);
+// Bumped whenever the layout of an event payload changes
+pub (crate) const EVENT_VERSION: u32 = 1;
+
+// Every project event is published with the topics
+// (event name, project id, client, freelancer) so indexers can filter on them.
+fn publish_project_event<D: IntoVal<Env, Val>>(e: &Env, name: &str, project_id: u128, project: &Project, data: D) {
+    let topics = (Symbol::new(e, name), project_id, project.client.clone(), project.freelancer.clone());
+    e.events().publish(topics, data);
+}
 
-    let project_id_val: Val = project_id.into_val(e);
-    let client_val: Val = client.into_val(e);
-    let freelancer_val: Val = freelancer.into_val(e);
-    let prices_val: Val = prices.into_val(e);
+// ------ Projects
 
-    let event_payload = vec![e, project_id_val, client_val, freelancer_val, prices_val];
-    e.events().publish(topics, event_payload);
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectCreatedEvent {
+    pub version: u32,
+    pub kind: ProjectKind,
+    pub prices: Vec<u128>,
 }
 
-// Event for project completed
-pub (crate) fn project_completed(e: &Env, project_id: DataKey) {
-    let topics = (symbol_short!("p_c"),); // c -> completed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectCompletedEvent {
+    pub version: u32,
+    pub earned_amount: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectCancelledEvent {
+    pub version: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectRefundedEvent {
+    pub version: u32,
+    pub recipient: Address,
+    pub amount: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementEvent {
+    pub version: u32,
+    pub party: Address,
+    pub to_freelancer: u128,
+    pub to_client: u128,
+}
 
-    let project_id_val: Val = project_id.into_val(e);
-    e.events().publish(topics, project_id_val);
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KillFeeEvent {
+    pub version: u32,
+    pub objective_id: u128,
+    pub fee: u128,
+    pub refunded: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectWithdrawnEvent {
+    pub version: u32,
+    pub reason: WithdrawalReason,
+    pub refunded: u128,
+}
+
+// Event for project created
+pub (crate) fn project_created(e: &Env, project_id: u128, project: &Project, prices: Vec<u128>) {
+    let event = ProjectCreatedEvent { version: EVENT_VERSION, kind: project.kind.clone(), prices };
+    publish_project_event(e, "project_created", project_id, project, event);
+}
+
+// Event for project completed
+pub (crate) fn project_completed(e: &Env, project_id: u128, project: &Project) {
+    let event = ProjectCompletedEvent { version: EVENT_VERSION, earned_amount: project.earned_amount };
+    publish_project_event(e, "project_completed", project_id, project, event);
 }
 
 // Event for project cancelled
-pub (crate) fn project_cancelled(e: &Env, project_id: DataKey) {
-    let topics = (symbol_short!("p_cd"),); // cd -> cancelled
+pub (crate) fn project_cancelled(e: &Env, project_id: u128, project: &Project) {
+    let event = ProjectCancelledEvent { version: EVENT_VERSION };
+    publish_project_event(e, "project_cancelled", project_id, project, event);
+}
 
-    let project_id_val: Val = project_id.into_val(e);
-    e.events().publish(topics, project_id_val);
+// Event for project escrow refunded
+pub (crate) fn project_refunded(e: &Env, project_id: u128, project: &Project, recipient: Address, amount: u128) {
+    let event = ProjectRefundedEvent { version: EVENT_VERSION, recipient, amount };
+    publish_project_event(e, "project_refunded", project_id, project, event);
 }
 
-// Event for project refunded
-pub (crate) fn project_refunded(e: &Env, project_id: DataKey, client: Address, price: u128) {
-    let topics = (symbol_short!("p_rd"),); // rd -> refunded
+// Event for freelancer stepping out of the project, refunded is what went back to the client
+pub (crate) fn project_withdrawn(e: &Env, project_id: u128, project: &Project, reason: WithdrawalReason, refunded: u128) {
+    let event = ProjectWithdrawnEvent { version: EVENT_VERSION, reason, refunded };
+    publish_project_event(e, "project_withdrawn", project_id, project, event);
+}
 
-    let project_id_val: Val = project_id.into_val(e);
-    let client_val: Val = client.into_val(e);
-    let price_val: Val = price.into_val(e);
+// Event for escrow of a funded objective split between the parties on cancel
+pub (crate) fn kill_fee_paid(e: &Env, project_id: u128, project: &Project, objective_id: u128, fee: u128, refunded: u128) {
+    let event = KillFeeEvent { version: EVENT_VERSION, objective_id, fee, refunded };
+    publish_project_event(e, "kill_fee_paid", project_id, project, event);
+}
 
-    let event_payload = vec![e, project_id_val, client_val, price_val];
+// Event for settlement proposed, party is the proposer
+pub (crate) fn settlement_proposed(e: &Env, project_id: u128, project: &Project, proposer: Address, to_freelancer: u128, to_client: u128) {
+    let event = SettlementEvent { version: EVENT_VERSION, party: proposer, to_freelancer, to_client };
+    publish_project_event(e, "settlement_proposed", project_id, project, event);
+}
 
-    e.events().publish(topics, event_payload);
+// Event for pending settlement withdrawn or turned down, party is who dropped it
+pub (crate) fn settlement_rejected(e: &Env, project_id: u128, project: &Project, party: Address, to_freelancer: u128, to_client: u128) {
+    let event = SettlementEvent { version: EVENT_VERSION, party, to_freelancer, to_client };
+    publish_project_event(e, "settlement_rejected", project_id, project, event);
 }
 
+// Event for project terminated by a settlement, party is who accepted it
+pub (crate) fn project_settled(e: &Env, project_id: u128, project: &Project, party: Address, to_freelancer: u128, to_client: u128) {
+    let event = SettlementEvent { version: EVENT_VERSION, party, to_freelancer, to_client };
+    publish_project_event(e, "project_settled", project_id, project, event);
+}
 
 // ------ Objectives
 
-// Event for objective added
-pub (crate) fn objective_added(e: &Env, project_id: &DataKey, objective_id: u128, price: u128) {
-    let topics = (symbol_short!("ob_added"),);
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ObjectiveEvent {
+    pub version: u32,
+    pub objective_id: u128,
+    pub amount: u128,
+}
+
+// Event for objective added, amount is the objective price
+pub (crate) fn objective_added(e: &Env, project_id: u128, project: &Project, objective_id: u128, price: u128) {
+    let event = ObjectiveEvent { version: EVENT_VERSION, objective_id, amount: price };
+    publish_project_event(e, "objective_added", project_id, project, event);
+}
+
+// Event for objective funded, amount is what went into escrow
+pub (crate) fn objective_funded(e: &Env, project_id: u128, project: &Project, objective_id: u128, amount: u128) {
+    let event = ObjectiveEvent { version: EVENT_VERSION, objective_id, amount };
+    publish_project_event(e, "objective_funded", project_id, project, event);
+}
+
+// Event for escrow of an uncompleted objective returned to the client
+pub (crate) fn objective_refunded(e: &Env, project_id: u128, project: &Project, objective_id: u128, amount: u128) {
+    let event = ObjectiveEvent { version: EVENT_VERSION, objective_id, amount };
+    publish_project_event(e, "objective_refunded", project_id, project, event);
+}
+
+// Event for objective completed, amount is what was paid to the freelancer
+pub (crate) fn objective_completed(e: &Env, project_id: u128, project: &Project, objective_id: u128, amount: u128) {
+    let event = ObjectiveEvent { version: EVENT_VERSION, objective_id, amount };
+    publish_project_event(e, "objective_completed", project_id, project, event);
+}
+
+// ------ Streams
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamAddedEvent {
+    pub version: u32,
+    pub objective_id: u128,
+    pub price: u128,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+// Event for streaming objective added
+pub (crate) fn stream_added(e: &Env, project_id: u128, project: &Project, objective_id: u128, price: u128, start_time: u64, end_time: u64) {
+    let event = StreamAddedEvent { version: EVENT_VERSION, objective_id, price, start_time, end_time };
+    publish_project_event(e, "stream_added", project_id, project, event);
+}
+
+// Event for vested funds withdrawn by the freelancer
+pub (crate) fn stream_withdrawn(e: &Env, project_id: u128, project: &Project, objective_id: u128, amount: u128) {
+    let event = ObjectiveEvent { version: EVENT_VERSION, objective_id, amount };
+    publish_project_event(e, "stream_withdrawn", project_id, project, event);
+}
+
+// Event for stream cancelled by the client, amount is the unvested refund
+pub (crate) fn stream_cancelled(e: &Env, project_id: u128, project: &Project, objective_id: u128, refunded: u128) {
+    let event = ObjectiveEvent { version: EVENT_VERSION, objective_id, amount: refunded };
+    publish_project_event(e, "stream_cancelled", project_id, project, event);
+}
+
+// ------ Retainers
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetainerChargedEvent {
+    pub version: u32,
+    pub period: u32,
+    pub amount: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetainerStateEvent {
+    pub version: u32,
+    pub paused: bool,
+    pub next_charge: u64,
+}
+
+// Event for retainer period charged and paid to the freelancer
+pub (crate) fn retainer_charged(e: &Env, project_id: u128, project: &Project, period: u32, amount: u128) {
+    let event = RetainerChargedEvent { version: EVENT_VERSION, period, amount };
+    publish_project_event(e, "retainer_charged", project_id, project, event);
+}
+
+// Event for retainer paused by the client
+pub (crate) fn retainer_paused(e: &Env, project_id: u128, project: &Project, next_charge: u64) {
+    let event = RetainerStateEvent { version: EVENT_VERSION, paused: true, next_charge };
+    publish_project_event(e, "retainer_paused", project_id, project, event);
+}
+
+// Event for retainer resumed by the client
+pub (crate) fn retainer_resumed(e: &Env, project_id: u128, project: &Project, next_charge: u64) {
+    let event = RetainerStateEvent { version: EVENT_VERSION, paused: false, next_charge };
+    publish_project_event(e, "retainer_resumed", project_id, project, event);
+}
+
+// ------ Hourly
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HourlyFundedEvent {
+    pub version: u32,
+    pub amount: u128,
+    pub escrow_balance: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimeLoggedEvent {
+    pub version: u32,
+    pub week: u32,
+    pub hours: u32,
+    pub description_hash: BytesN<32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WeekReviewedEvent {
+    pub version: u32,
+    pub week: u32,
+    pub reviewer: Address,
+    pub amount: u128,
+}
+
+// Event for hourly escrow funded by the client
+pub (crate) fn hourly_funded(e: &Env, project_id: u128, project: &Project, amount: u128) {
+    let event = HourlyFundedEvent { version: EVENT_VERSION, amount, escrow_balance: project.contract_balance };
+    publish_project_event(e, "hourly_funded", project_id, project, event);
+}
+
+// Event for time entry logged by the freelancer
+pub (crate) fn time_logged(e: &Env, project_id: u128, project: &Project, week: u32, hours: u32, description_hash: BytesN<32>) {
+    let event = TimeLoggedEvent { version: EVENT_VERSION, week, hours, description_hash };
+    publish_project_event(e, "time_logged", project_id, project, event);
+}
+
+// Event for week approved and paid out
+pub (crate) fn week_approved(e: &Env, project_id: u128, project: &Project, week: u32, reviewer: Address, amount: u128) {
+    let event = WeekReviewedEvent { version: EVENT_VERSION, week, reviewer, amount };
+    publish_project_event(e, "week_approved", project_id, project, event);
+}
+
+// Event for week disputed by the client
+pub (crate) fn week_disputed(e: &Env, project_id: u128, project: &Project, week: u32, reviewer: Address) {
+    let event = WeekReviewedEvent { version: EVENT_VERSION, week, reviewer, amount: 0 };
+    publish_project_event(e, "week_disputed", project_id, project, event);
+}
+
+// ------ Reputation
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectRatedEvent {
+    pub version: u32,
+    pub rater: Address,
+    pub ratee: Address,
+    pub score: u32,
+    pub comment_hash: BytesN<32>,
+}
+
+// Event for rating left by one party of a project for the other
+pub (crate) fn project_rated(e: &Env, project_id: u128, project: &Project, rater: Address, ratee: Address, score: u32, comment_hash: BytesN<32>) {
+    let event = ProjectRatedEvent { version: EVENT_VERSION, rater, ratee, score, comment_hash };
+    publish_project_event(e, "project_rated", project_id, project, event);
+}
+
+// ------ Delegates
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DelegateSetEvent {
+    pub version: u32,
+    pub delegate: Address,
+    pub permissions: DelegatePermissions,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DelegateRemovedEvent {
+    pub version: u32,
+    pub delegate: Address,
+}
+
+// Event for delegate added or its permissions changed
+pub (crate) fn delegate_set(e: &Env, project_id: u128, project: &Project, delegate: Address, permissions: DelegatePermissions) {
+    let event = DelegateSetEvent { version: EVENT_VERSION, delegate, permissions };
+    publish_project_event(e, "delegate_set", project_id, project, event);
+}
+
+// Event for delegate removed
+pub (crate) fn delegate_removed(e: &Env, project_id: u128, project: &Project, delegate: Address) {
+    let event = DelegateRemovedEvent { version: EVENT_VERSION, delegate };
+    publish_project_event(e, "delegate_removed", project_id, project, event);
+}
+
+// ------ Organizations
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrgEvent {
+    pub version: u32,
+    pub members: u32,
+    pub threshold: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrgActionApprovedEvent {
+    pub version: u32,
+    pub member: Address,
+    pub action: OrgAction,
+    pub approvals: u32,
+    pub threshold: u32,
+}
+
+// Event for organization created
+pub (crate) fn org_created(e: &Env, org_id: u64, account: Address, members: u32, threshold: u32) {
+    let topics = (Symbol::new(e, "org_created"), org_id, account);
+    e.events().publish(topics, OrgEvent { version: EVENT_VERSION, members, threshold });
+}
+
+// Event for organization members or threshold changed
+pub (crate) fn org_updated(e: &Env, org_id: u64, account: Address, members: u32, threshold: u32) {
+    let topics = (Symbol::new(e, "org_updated"), org_id, account);
+    e.events().publish(topics, OrgEvent { version: EVENT_VERSION, members, threshold });
+}
+
+// Event for organization member approving an action on a project
+pub (crate) fn org_action_approved(e: &Env, project_id: u128, project: &Project, member: Address, action: OrgAction, approvals: u32, threshold: u32) {
+    let event = OrgActionApprovedEvent { version: EVENT_VERSION, member, action, approvals, threshold };
+    publish_project_event(e, "org_action_approved", project_id, project, event);
+}
+
+// ------ Users
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserEvent {
+    pub version: u32,
+    pub user_id: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserDeletedEvent {
+    pub version: u32,
+    pub user_id: u64,
+    pub blocked: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecoverySetEvent {
+    pub version: u32,
+    pub guardians: u32,
+    pub threshold: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecoveryApprovedEvent {
+    pub version: u32,
+    pub guardian: Address,
+    pub approvals: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserMigratedEvent {
+    pub version: u32,
+    pub projects: u32,
+}
+
+// Event for user registered
+pub (crate) fn user_registered(e: &Env, user: Address, user_id: u64) {
+    let topics = (Symbol::new(e, "user_registered"), user);
+    e.events().publish(topics, UserEvent { version: EVENT_VERSION, user_id });
+}
+
+// Event for user profile updated
+pub (crate) fn user_updated(e: &Env, user: Address, user_id: u64) {
+    let topics = (Symbol::new(e, "user_updated"), user);
+    e.events().publish(topics, UserEvent { version: EVENT_VERSION, user_id });
+}
+
+// Event for user profile deleted
+pub (crate) fn user_deleted(e: &Env, user: Address, user_id: u64, blocked: bool) {
+    let topics = (Symbol::new(e, "user_deleted"), user);
+    e.events().publish(topics, UserDeletedEvent { version: EVENT_VERSION, user_id, blocked });
+}
+
+// Event for recovery guardians configured
+pub (crate) fn recovery_set(e: &Env, user: Address, guardians: u32, threshold: u32) {
+    let topics = (Symbol::new(e, "recovery_set"), user);
+    e.events().publish(topics, RecoverySetEvent { version: EVENT_VERSION, guardians, threshold });
+}
+
+// Event for guardian approving the recovery of a lost address
+pub (crate) fn recovery_approved(e: &Env, lost: Address, new_address: Address, guardian: Address, approvals: u32) {
+    let topics = (Symbol::new(e, "recovery_approved"), lost, new_address);
+    e.events().publish(topics, RecoveryApprovedEvent { version: EVENT_VERSION, guardian, approvals });
+}
+
+// Event for user moved to a new address
+pub (crate) fn user_migrated(e: &Env, old: Address, new: Address, projects: u32) {
+    let topics = (Symbol::new(e, "user_migrated"), old, new);
+    e.events().publish(topics, UserMigratedEvent { version: EVENT_VERSION, projects });
+}
+
+// ------ Templates
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TemplateEvent {
+    pub version: u32,
+    pub objectives: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TemplateUsedEvent {
+    pub version: u32,
+    pub project_id: u128,
+    pub scale: u32,
+}
+
+// Event for template created
+pub (crate) fn template_created(e: &Env, template_id: u64, owner: Address, objectives: u32) {
+    let topics = (Symbol::new(e, "template_created"), template_id, owner);
+    e.events().publish(topics, TemplateEvent { version: EVENT_VERSION, objectives });
+}
+
+// Event for template removed by its owner
+pub (crate) fn template_removed(e: &Env, template_id: u64, owner: Address) {
+    let topics = (Symbol::new(e, "template_removed"), template_id, owner);
+    e.events().publish(topics, TemplateEvent { version: EVENT_VERSION, objectives: 0 });
+}
+
+// Event for project instantiated from a template
+pub (crate) fn template_used(e: &Env, template_id: u64, owner: Address, project_id: u128, scale: u32) {
+    let topics = (Symbol::new(e, "template_used"), template_id, owner);
+    e.events().publish(topics, TemplateUsedEvent { version: EVENT_VERSION, project_id, scale });
+}
+
+// ------ Funders
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FunderEvent {
+    pub version: u32,
+    pub funder: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ObjectiveSponsoredEvent {
+    pub version: u32,
+    pub objective_id: u128,
+    pub funder: Address,
+    pub amount: u128,
+}
+
+// Event for client authorizing a third party to fund its objectives
+pub (crate) fn funder_added(e: &Env, project_id: u128, project: &Project, funder: Address) {
+    let event = FunderEvent { version: EVENT_VERSION, funder };
+    publish_project_event(e, "funder_added", project_id, project, event);
+}
+
+// Event for client revoking a funder, past contributions are still refunded to it
+pub (crate) fn funder_removed(e: &Env, project_id: u128, project: &Project, funder: Address) {
+    let event = FunderEvent { version: EVENT_VERSION, funder };
+    publish_project_event(e, "funder_removed", project_id, project, event);
+}
+
+// Event for objective escrow paid by a funder instead of the client
+pub (crate) fn objective_sponsored(e: &Env, project_id: u128, project: &Project, objective_id: u128, funder: Address, amount: u128) {
+    let event = ObjectiveSponsoredEvent { version: EVENT_VERSION, objective_id, funder, amount };
+    publish_project_event(e, "objective_sponsored", project_id, project, event);
+}
+
+// ------ Crowdfunding
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CrowdfundingOpenedEvent {
+    pub version: u32,
+    pub objective_id: u128,
+    pub deadline: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContributionEvent {
+    pub version: u32,
+    pub objective_id: u128,
+    pub contributor: Address,
+    pub amount: u128,
+    pub raised: u128,
+}
 
-    let project_id_val: Val = project_id.into_val(e);
-    let objective_id_val: Val = objective_id.into_val(e);
-    let price_val: Val = price.into_val(e);
+// Event for client opening an objective to contributions until the deadline
+pub (crate) fn crowdfunding_opened(e: &Env, project_id: u128, project: &Project, objective_id: u128, deadline: u64) {
+    let event = CrowdfundingOpenedEvent { version: EVENT_VERSION, objective_id, deadline };
+    publish_project_event(e, "crowdfunding_opened", project_id, project, event);
+}
 
-    let event_payload = vec![e, project_id_val, objective_id_val, price_val];
-    e.events().publish(topics, event_payload);
+// Event for contribution to a crowdfunded objective, raised is the total so far
+pub (crate) fn objective_contributed(e: &Env, project_id: u128, project: &Project, objective_id: u128, contributor: Address, amount: u128, raised: u128) {
+    let event = ContributionEvent { version: EVENT_VERSION, objective_id, contributor, amount, raised };
+    publish_project_event(e, "objective_contributed", project_id, project, event);
 }
 
-// Event for objective funded
-pub (crate) fn objective_funded(e: &Env, project_id: DataKey, objective_id: u128, half_price: u128) {
-    let topics = (symbol_short!("ob_funded"),);
+// Event for crowdfunding that missed its deadline, amount is what went back to contributors
+pub (crate) fn crowdfunding_expired(e: &Env, project_id: u128, project: &Project, objective_id: u128, amount: u128) {
+    let event = ObjectiveEvent { version: EVENT_VERSION, objective_id, amount };
+    publish_project_event(e, "crowdfunding_expired", project_id, project, event);
+}
 
-    let project_id_val: Val = project_id.into_val(e);
-    let objective_id_val: Val = objective_id.into_val(e);
-    let half_price_val: Val = half_price.into_val(e);
+// ------ Holdbacks
 
-    let event_payload = vec![e, project_id_val, objective_id_val, half_price_val];
-    e.events().publish(topics, event_payload);
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HoldbackStartedEvent {
+    pub version: u32,
+    pub objective_id: u128,
+    pub amount: u128,
+    pub release_at: u64,
 }
 
-// Event for objective funded
-pub (crate) fn objective_completed(e: &Env, project_id: DataKey, objective_id: u128, full_price: u128) {
-    let topics = (symbol_short!("ob_c"),); // c -> completed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HoldbackReleasedEvent {
+    pub version: u32,
+    pub objective_id: u128,
+    pub recipient: Address,
+    pub amount: u128,
+}
 
-    let project_id_val: Val = project_id.into_val(e);
-    let objective_id_val: Val = objective_id.into_val(e);
-    let full_price_val: Val = full_price.into_val(e);
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefectClaimedEvent {
+    pub version: u32,
+    pub objective_id: u128,
+    pub claim_hash: BytesN<32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimSettlementEvent {
+    pub version: u32,
+    pub objective_id: u128,
+    pub party: Address,
+    pub to_freelancer: u128,
+    pub to_client: u128,
+}
+
+// Event for part of a completed objective's price kept in escrow until release_at
+pub (crate) fn holdback_started(e: &Env, project_id: u128, project: &Project, objective_id: u128, amount: u128, release_at: u64) {
+    let event = HoldbackStartedEvent { version: EVENT_VERSION, objective_id, amount, release_at };
+    publish_project_event(e, "holdback_started", project_id, project, event);
+}
 
-    let event_payload = vec![e, project_id_val, objective_id_val, full_price_val];
-    e.events().publish(topics, event_payload);
-}
\ No newline at end of file
+// Event for holdback paid out, to the freelancer or back to the client after a defect claim
+pub (crate) fn holdback_released(e: &Env, project_id: u128, project: &Project, objective_id: u128, recipient: Address, amount: u128) {
+    let event = HoldbackReleasedEvent { version: EVENT_VERSION, objective_id, recipient, amount };
+    publish_project_event(e, "holdback_released", project_id, project, event);
+}
+
+// Event for client raising a defect claim on a holdback
+pub (crate) fn defect_claimed(e: &Env, project_id: u128, project: &Project, objective_id: u128, claim_hash: BytesN<32>) {
+    let event = DefectClaimedEvent { version: EVENT_VERSION, objective_id, claim_hash };
+    publish_project_event(e, "defect_claimed", project_id, project, event);
+}
+
+// Event for a split of a disputed holdback proposed, party is the proposer
+pub (crate) fn claim_settlement_proposed(e: &Env, project_id: u128, project: &Project, objective_id: u128, proposer: Address, to_freelancer: u128, to_client: u128) {
+    let event = ClaimSettlementEvent { version: EVENT_VERSION, objective_id, party: proposer, to_freelancer, to_client };
+    publish_project_event(e, "claim_settlement_proposed", project_id, project, event);
+}
+
+// Event for disputed holdback split as agreed, party is who accepted it
+pub (crate) fn claim_settled(e: &Env, project_id: u128, project: &Project, objective_id: u128, party: Address, to_freelancer: u128, to_client: u128) {
+    let event = ClaimSettlementEvent { version: EVENT_VERSION, objective_id, party, to_freelancer, to_client };
+    publish_project_event(e, "claim_settled", project_id, project, event);
+}
+
+// ------ Withdrawals
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FundsCreditedEvent {
+    pub version: u32,
+    pub project_id: u128,
+    pub token: Address,
+    pub amount: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FundsWithdrawnEvent {
+    pub version: u32,
+    pub token: Address,
+    pub destination: Address,
+    pub amount: u128,
+}
+
+// Event for a payout or refund added to the claimable balance of an address
+pub (crate) fn funds_credited(e: &Env, address: Address, project_id: u128, token: Address, amount: u128) {
+    let topics = (Symbol::new(e, "funds_credited"), address);
+    e.events().publish(topics, FundsCreditedEvent { version: EVENT_VERSION, project_id, token, amount });
+}
+
+// Event for claimable balance withdrawn by its owner
+pub (crate) fn funds_withdrawn(e: &Env, address: Address, token: Address, destination: Address, amount: u128) {
+    let topics = (Symbol::new(e, "funds_withdrawn"), address);
+    e.events().publish(topics, FundsWithdrawnEvent { version: EVENT_VERSION, token, destination, amount });
+}
+
+// ------ Settings
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegistrationRequiredEvent {
+    pub version: u32,
+    pub required: bool,
+}
+
+// Event for the admin changing whether projects need registered users
+pub (crate) fn registration_required_set(e: &Env, admin: Address, required: bool) {
+    let topics = (Symbol::new(e, "registration_required_set"), admin);
+    e.events().publish(topics, RegistrationRequiredEvent { version: EVENT_VERSION, required });
+}
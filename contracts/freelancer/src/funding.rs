@@ -0,0 +1,76 @@
+use soroban_sdk::{Address, Env, Map};
+
+use crate::storage::{add_address_project, read_persistent, write_persistent};
+use crate::storage_types::{DataKey, Project};
+
+pub fn is_funder(e: &Env, project_id: u128, funder: &Address) -> bool {
+    read_persistent::<bool>(e, &DataKey::Funder(project_id, funder.clone())).is_some()
+}
+
+pub fn write_funder(e: &Env, project_id: u128, funder: &Address) {
+    write_persistent(e, &DataKey::Funder(project_id, funder.clone()), &true);
+    add_address_project(e, funder, project_id);
+}
+
+pub fn remove_funder(e: &Env, project_id: u128, funder: &Address) {
+    e.storage().persistent().remove(&DataKey::Funder(project_id, funder.clone()));
+}
+
+// What each funder put into an objective's escrow, empty when the client funded it
+pub fn read_contributions(e: &Env, project_id: u128, objective_id: u128) -> Map<Address, u128> {
+    read_persistent(e, &DataKey::Contributions(project_id, objective_id))
+        .unwrap_or(Map::new(e))
+}
+
+pub fn add_contribution(e: &Env, project_id: u128, objective_id: u128, funder: &Address, amount: u128) {
+    let mut contributions = read_contributions(e, project_id, objective_id);
+    let contributed = contributions.get(funder.clone()).unwrap_or(0);
+    contributions.set(funder.clone(), contributed + amount);
+
+    write_persistent(e, &DataKey::Contributions(project_id, objective_id), &contributions);
+    add_address_project(e, funder, project_id);
+}
+
+pub fn remove_contributions(e: &Env, project_id: u128, objective_id: u128) {
+    e.storage().persistent().remove(&DataKey::Contributions(project_id, objective_id));
+}
+
+// Splits a refund pro rata to what each funder contributed, rounding dust goes to the last one
+pub fn refund_shares(e: &Env, contributions: &Map<Address, u128>, amount: u128) -> Map<Address, u128> {
+    let mut shares: Map<Address, u128> = Map::new(e);
+    let total: u128 = contributions.values().iter().sum();
+    if total == 0 {
+        return shares;
+    }
+
+    let mut left = amount;
+    for (i, (funder, contributed)) in contributions.iter().enumerate() {
+        let share = if i as u32 + 1 == contributions.len() {
+            left
+        } else {
+            amount * contributed / total
+        };
+
+        left -= share;
+        shares.set(funder, share);
+    }
+
+    shares
+}
+
+// Moves the funder authorization and contributions of `old` so refunds reach `new`
+pub fn move_funder(e: &Env, project_id: u128, project: &Project, old: &Address, new: &Address) {
+    if is_funder(e, project_id, old) {
+        remove_funder(e, project_id, old);
+        write_funder(e, project_id, new);
+    }
+
+    for (objective_id, _) in project.objectives.iter() {
+        let mut contributions = read_contributions(e, project_id, objective_id);
+        if let Some(amount) = contributions.get(old.clone()) {
+            contributions.remove(old.clone());
+            write_persistent(e, &DataKey::Contributions(project_id, objective_id), &contributions);
+            add_contribution(e, project_id, objective_id, new, amount);
+        }
+    }
+}
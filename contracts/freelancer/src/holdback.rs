@@ -0,0 +1,50 @@
+use soroban_sdk::{Env, Map};
+
+use crate::storage::{read_persistent, write_persistent};
+use crate::storage_types::{DataKey, Holdback, Project};
+
+pub fn read_holdback(e: &Env, project_id: u128, objective_id: u128) -> Option<Holdback> {
+    read_persistent(e, &DataKey::Holdback(project_id, objective_id))
+}
+
+pub fn write_holdback(e: &Env, project_id: u128, objective_id: u128, holdback: &Holdback) {
+    write_persistent(e, &DataKey::Holdback(project_id, objective_id), holdback);
+}
+
+pub fn remove_holdback(e: &Env, project_id: u128, objective_id: u128) {
+    e.storage().persistent().remove(&DataKey::Holdback(project_id, objective_id));
+}
+
+// The holdback of an objective whose defect claim is waiting for a resolution
+pub fn read_claimed_holdback(e: &Env, project_id: u128, objective_id: u128) -> Holdback {
+    match read_holdback(e, project_id, objective_id) {
+        Some(holdback) if holdback.claimed => holdback,
+        Some(_) => panic!("No defect claim raised"),
+        None => panic!("No holdback for this objective"),
+    }
+}
+
+// Holdbacks still in escrow, by objective id
+pub fn pending_holdbacks(e: &Env, project_id: u128, project: &Project) -> Map<u128, Holdback> {
+    let mut holdbacks: Map<u128, Holdback> = Map::new(e);
+
+    for (objective_id, objective) in project.objectives.iter() {
+        if !objective.completed {
+            continue;
+        }
+
+        if let Some(holdback) = read_holdback(e, project_id, objective_id) {
+            holdbacks.set(objective_id, holdback);
+        }
+    }
+
+    holdbacks
+}
+
+pub fn held_back_amount(e: &Env, project_id: u128, project: &Project) -> u128 {
+    pending_holdbacks(e, project_id, project)
+        .values()
+        .iter()
+        .map(|holdback| holdback.amount)
+        .sum()
+}
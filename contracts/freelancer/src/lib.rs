@@ -8,6 +8,17 @@ mod admin;
 mod metadata;
 mod token;
 mod events;
+mod stream;
+mod summary;
+mod claims;
+mod holdback;
+mod funding;
+mod crowdfund;
+mod reputation;
+mod users;
+mod organization;
+mod delegate;
+mod template;
 mod contract;
 mod test;
 
@@ -8,22 +8,26 @@ pub fn write_metadata(e: &Env, metadata: TokenMetadata) {
 
 // They are not used at the moment 👇
 
+#[allow(dead_code)]
 pub fn read_decimal(e: &Env) -> u32 {
     let util = TokenUtils::new(e);
     util.metadata().get_metadata().decimal
 }
 
+#[allow(dead_code)]
 pub fn read_name(e: &Env) -> String {
     let util = TokenUtils::new(e);
     util.metadata().get_metadata().name
 }
 
+#[allow(dead_code)]
 pub fn read_symbol(e: &Env) -> String {
     let util = TokenUtils::new(e);
     util.metadata().get_metadata().symbol
 }
 
 
+#[allow(dead_code)]
 pub fn read_metadata(e: &Env) -> TokenMetadata {
     let util = TokenUtils::new(e);
     util.metadata().get_metadata()
@@ -0,0 +1,117 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::storage::{get_project, read_address_projects, read_persistent, write_persistent};
+use crate::storage_types::{DataKey, OrgAction, OrgRole, Organization};
+
+pub fn read_organization(e: &Env, org_id: u64) -> Organization {
+    match read_persistent(e, &DataKey::Organization(org_id)) {
+        Some(org) => org,
+        None => panic!("Organization not found"),
+    }
+}
+
+pub fn read_organization_by_account(e: &Env, account: &Address) -> Option<Organization> {
+    let org_id: u64 = read_persistent(e, &DataKey::OrgAccount(account.clone()))?;
+    Some(read_organization(e, org_id))
+}
+
+pub fn write_organization(e: &Env, org: &Organization) {
+    write_persistent(e, &DataKey::Organization(org.id), org);
+}
+
+pub fn is_org_approver(org: &Organization, member: &Address) -> bool {
+    matches!(
+        org.members.get(member.clone()),
+        Some(OrgRole::Owner) | Some(OrgRole::Approver)
+    )
+}
+
+pub fn approver_count(org: &Organization) -> u32 {
+    org.members
+        .values()
+        .iter()
+        .filter(|role| *role != OrgRole::Viewer)
+        .count() as u32
+}
+
+pub fn require_org_owner(org: &Organization, caller: &Address) {
+    caller.require_auth();
+    if org.members.get(caller.clone()) != Some(OrgRole::Owner) {
+        panic!("Only an organization owner can manage the organization");
+    }
+}
+
+// Authenticates `actor` acting as `party`: either the party itself, or an
+// owner/approver of the organization whose account is `party`.
+pub fn require_party(e: &Env, party: &Address, actor: &Address) {
+    actor.require_auth();
+
+    if actor == party {
+        return;
+    }
+
+    match read_organization_by_account(e, party) {
+        Some(org) if is_org_approver(&org, actor) => {}
+        _ => panic!("Not authorized to act for this party"),
+    }
+}
+
+// Organizations an address was made a member of
+pub fn read_member_orgs(e: &Env, member: &Address) -> Vec<u64> {
+    read_persistent(e, &DataKey::MemberOrgs(member.clone()))
+        .unwrap_or(Vec::new(e))
+}
+
+pub fn add_member_org(e: &Env, member: &Address, org_id: u64) {
+    let mut org_ids = read_member_orgs(e, member);
+    if !org_ids.contains(org_id) {
+        org_ids.push_back(org_id);
+        write_persistent(e, &DataKey::MemberOrgs(member.clone()), &org_ids);
+    }
+}
+
+// Points approvals `old` gave on the project's pending org actions at `new`
+fn move_org_approvals(e: &Env, project_id: u128, old: &Address, new: &Address) {
+    let (project, _) = get_project(e, project_id);
+    for (objective_id, _) in project.objectives.iter() {
+        for action in [OrgAction::FundObjective(objective_id), OrgAction::CompleteObjective(objective_id)] {
+            let key = DataKey::OrgApprovals(project_id, action);
+            let mut approvals: Vec<Address> = match read_persistent(e, &key) {
+                Some(approvals) => approvals,
+                None => continue,
+            };
+
+            if let Some(index) = approvals.first_index_of(old) {
+                approvals.set(index, new.clone());
+                write_persistent(e, &key, &approvals);
+            }
+        }
+    }
+}
+
+// Moves an organization account and every membership of `old` over to `new`
+pub fn move_organizations(e: &Env, old: &Address, new: &Address) {
+    if let Some(org_id) = read_persistent::<u64>(e, &DataKey::OrgAccount(old.clone())) {
+        let mut org = read_organization(e, org_id);
+        org.account = new.clone();
+        write_organization(e, &org);
+
+        e.storage().persistent().remove(&DataKey::OrgAccount(old.clone()));
+        write_persistent(e, &DataKey::OrgAccount(new.clone()), &org_id);
+    }
+
+    for org_id in read_member_orgs(e, old).iter() {
+        let mut org = read_organization(e, org_id);
+        if let Some(role) = org.members.get(old.clone()) {
+            org.members.remove(old.clone());
+            org.members.set(new.clone(), role);
+            write_organization(e, &org);
+            add_member_org(e, new, org_id);
+
+            for project_id in read_address_projects(e, &org.account).iter() {
+                move_org_approvals(e, project_id, old, new);
+            }
+        }
+    }
+    e.storage().persistent().remove(&DataKey::MemberOrgs(old.clone()));
+}
@@ -0,0 +1,77 @@
+use soroban_sdk::{Address, Env};
+
+use crate::storage::{read_persistent, write_persistent};
+use crate::storage_types::{DataKey, Project, Rating, Reputation};
+
+pub const MIN_RATING: u32 = 1;
+pub const MAX_RATING: u32 = 5;
+
+pub fn read_reputation(e: &Env, user: &Address) -> Reputation {
+    let key = DataKey::Reputation(user.clone());
+    read_persistent(e, &key).unwrap_or(Reputation {
+        rating_count: 0,
+        rating_total: 0,
+        average_rating: 0,
+        completed_projects: 0,
+        completed_volume: 0,
+    })
+}
+
+pub fn write_reputation(e: &Env, user: &Address, reputation: &Reputation) {
+    let key = DataKey::Reputation(user.clone());
+    write_persistent(e, &key, reputation);
+}
+
+pub fn record_rating(e: &Env, user: &Address, score: u32) {
+    let mut reputation = read_reputation(e, user);
+    reputation.rating_count += 1;
+    reputation.rating_total += score;
+    reputation.average_rating = reputation.rating_total * 100 / reputation.rating_count;
+    write_reputation(e, user, &reputation);
+}
+
+pub fn record_completed_project(e: &Env, user: &Address, volume: u128) {
+    let mut reputation = read_reputation(e, user);
+    reputation.completed_projects += 1;
+    reputation.completed_volume += volume;
+    write_reputation(e, user, &reputation);
+}
+
+pub fn move_reputation(e: &Env, from: &Address, to: &Address) {
+    let key = DataKey::Reputation(from.clone());
+    let moved: Reputation = match read_persistent(e, &key) {
+        Some(reputation) => reputation,
+        None => return,
+    };
+
+    let mut reputation = read_reputation(e, to);
+    reputation.rating_count += moved.rating_count;
+    reputation.rating_total += moved.rating_total;
+    reputation.average_rating = (reputation.rating_total * 100)
+        .checked_div(reputation.rating_count)
+        .unwrap_or(0);
+    reputation.completed_projects += moved.completed_projects;
+    reputation.completed_volume += moved.completed_volume;
+
+    write_reputation(e, to, &reputation);
+    e.storage().persistent().remove(&key);
+}
+
+// Moves the rating `old` left on a project and points ratings it received at `new`
+pub fn move_rating(e: &Env, project_id: u128, project: &Project, old: &Address, new: &Address) {
+    if let Some(mut rating) = read_persistent::<Rating>(e, &DataKey::Rating(project_id, old.clone())) {
+        rating.rater = new.clone();
+        e.storage().persistent().remove(&DataKey::Rating(project_id, old.clone()));
+        write_persistent(e, &DataKey::Rating(project_id, new.clone()), &rating);
+    }
+
+    for party in [&project.client, &project.freelancer] {
+        let key = DataKey::Rating(project_id, party.clone());
+        if let Some(mut rating) = read_persistent::<Rating>(e, &key) {
+            if rating.ratee == *old {
+                rating.ratee = new.clone();
+                write_persistent(e, &key, &rating);
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use soroban_sdk::{contracttype, Env, symbol_short, Vec};
+use soroban_sdk::{contracttype, Address, Env, IntoVal, symbol_short, TryFromVal, Val, Vec};
 
-use crate::storage_types::{ Project, DataKey };
+use crate::storage_types::{ Project, DataKey, Timesheet, TimesheetStatus, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD };
 
 #[derive(Clone)]
 #[contracttype]
@@ -18,6 +18,62 @@ pub fn get_project(e: &Env, project_id: u128) -> (Project, DataKey) {
     (project, project_key)
 }
 
+// Records that grow with each project live in persistent storage, the instance
+// entry is loaded on every call. Their TTL is extended whenever they are touched.
+pub fn read_persistent<V: TryFromVal<Env, Val>>(e: &Env, key: &DataKey) -> Option<V> {
+    let value = e.storage().persistent().get(key);
+    if value.is_some() {
+        e.storage()
+            .persistent()
+            .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+    value
+}
+
+pub fn write_persistent<V: IntoVal<Env, Val>>(e: &Env, key: &DataKey, value: &V) {
+    e.storage().persistent().set(key, value);
+    e.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+pub fn get_timesheet(e: &Env, project_id: u128, week: u32) -> (Timesheet, DataKey) {
+    let timesheet_key = DataKey::Timesheet(project_id, week);
+    let timesheet: Timesheet = read_persistent(e, &timesheet_key)
+        .unwrap_or(Timesheet {
+            entries: Vec::new(e),
+            hours: 0,
+            status: TimesheetStatus::Pending,
+        });
+    (timesheet, timesheet_key)
+}
+
+pub fn read_require_registration(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get(&DataKey::RequireRegistration)
+        .unwrap_or(false)
+}
+
+pub fn write_require_registration(e: &Env, required: bool) {
+    e.storage()
+        .instance()
+        .set(&DataKey::RequireRegistration, &required);
+}
+
+pub fn next_project_id(e: &Env) -> u128 {
+    let contract_key = symbol_short!("pk");
+    let project_count: u128 = e
+        .storage()
+        .instance()
+        .get(&contract_key)
+        .unwrap_or(0)
+        + 1;
+
+    e.storage().instance().set(&contract_key, &project_count);
+    project_count
+}
+
 pub fn get_all_projects(e: Env) -> Vec<Project> {
     let project_count: u128 = e
         .storage()
@@ -35,4 +91,38 @@ pub fn get_all_projects(e: Env) -> Vec<Project> {
     }
 
     projects
-}
\ No newline at end of file
+}
+
+// Projects an address is the client, freelancer, delegate or a funder of, so
+// work done per user only walks that user's projects
+pub fn read_address_projects(e: &Env, address: &Address) -> Vec<u128> {
+    read_persistent(e, &DataKey::AddressProjects(address.clone()))
+        .unwrap_or(Vec::new(e))
+}
+
+pub fn add_address_project(e: &Env, address: &Address, project_id: u128) {
+    let mut project_ids = read_address_projects(e, address);
+    if !project_ids.contains(project_id) {
+        project_ids.push_back(project_id);
+        write_persistent(e, &DataKey::AddressProjects(address.clone()), &project_ids);
+    }
+}
+
+// Moves `old` over to `new` where it is the client or the freelancer of the project
+pub fn rebind_project_parties(e: &Env, project_id: u128, old: &Address, new: &Address) -> bool {
+    let (mut project, project_key) = get_project(e, project_id);
+    if project.client != *old && project.freelancer != *old {
+        return false;
+    }
+
+    if project.client == *old {
+        project.client = new.clone();
+    }
+
+    if project.freelancer == *old {
+        project.freelancer = new.clone();
+    }
+
+    e.storage().instance().set(&project_key, &project);
+    true
+}
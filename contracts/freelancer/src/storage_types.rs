@@ -1,8 +1,29 @@
-use soroban_sdk::{contracttype, Address, Map, String};
+use soroban_sdk::{contracttype, Address, BytesN, Map, String, Symbol, Vec};
 
 pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
 pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
 pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub(crate) const DAY_IN_SECONDS: u64 = 24 * 60 * 60;
+pub(crate) const WEEK_IN_SECONDS: u64 = 7 * DAY_IN_SECONDS;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Stream {
+    pub start_time: u64,
+    pub end_time: u64,
+    pub withdrawn: u128,
+    pub cancelled_at: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ObjectiveKind {
+    Milestone,
+    Stream(Stream),
+}
 
 #[contracttype]
 #[derive(Clone)]
@@ -10,6 +31,65 @@ pub struct Objective {
     pub price: u128,
     pub half_paid: u128,
     pub completed: bool,
+    pub kind: ObjectiveKind,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Retainer {
+    pub amount: u128,
+    pub period: u64,
+    pub next_charge: u64,
+    pub periods_charged: u32,
+    pub paused: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HourlyTerms {
+    pub rate: u128,
+    pub weekly_cap: u32,
+    pub start_time: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProjectKind {
+    Milestones,
+    Retainer(Retainer),
+    Hourly(HourlyTerms),
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct TimeEntry {
+    pub hours: u32,
+    pub description_hash: BytesN<32>,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TimesheetStatus {
+    Pending,
+    Approved,
+    Disputed,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Timesheet {
+    pub entries: Vec<TimeEntry>,
+    pub hours: u32,
+    pub status: TimesheetStatus,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DelegatePermissions {
+    pub approve_objectives: bool,
+    pub add_objectives: bool,
+    pub review_timesheets: bool,
 }
 
 #[contracttype]
@@ -17,6 +97,8 @@ pub struct Objective {
 pub struct Project {
     pub client: Address,
     pub freelancer: Address,
+    // Token escrowed, paid out and refunded by this project, set once at creation
+    pub token: Address,
     pub objectives_count: u128,
     pub objectives: Map<u128, Objective>,
     pub completed_objectives: u128,
@@ -24,6 +106,91 @@ pub struct Project {
     pub contract_balance: u128,
     pub cancelled: bool,
     pub completed: bool,
+    // Ended by a settlement both parties agreed on, also counts as cancelled
+    pub terminated: bool,
+    // Ended by the freelancer stepping out, also counts as cancelled
+    pub withdrawn: bool,
+    pub kind: ProjectKind,
+    pub terms: ProjectTerms,
+}
+
+// Terms agreed when the project is created
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProjectTerms {
+    // Share of a funded but incomplete objective's escrow paid to the freelancer on cancel
+    pub kill_fee_percent: u32,
+    // Share of each objective's price kept in escrow for holdback_days after approval
+    pub holdback_percent: u32,
+    pub holdback_days: u32,
+}
+
+// Objective open to contributions from anyone until its full price is raised
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Crowdfund {
+    pub raised: u128,
+    pub deadline: u64,
+}
+
+// Part of a completed objective's price kept as a warranty
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Holdback {
+    pub amount: u128,
+    pub release_at: u64,
+    // Set when the client raised a defect claim, the holdback then waits for a resolution
+    pub claim_hash: BytesN<32>,
+    pub claimed: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WithdrawalReason {
+    Personal,
+    ScopeChange,
+    ClientUnresponsive,
+    PaymentIssue,
+    Other,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Withdrawal {
+    pub reason: WithdrawalReason,
+    pub timestamp: u64,
+}
+
+// Split of the escrow proposed by one party to end the project
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Settlement {
+    pub proposer: Address,
+    pub to_freelancer: u128,
+    pub to_client: u128,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProjectStatus {
+    Active,
+    Paused,
+    Completed,
+    Cancelled,
+    Terminated,
+    Withdrawn,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectSummary {
+    pub status: ProjectStatus,
+    pub total_price: u128,
+    pub escrowed: u128,
+    pub paid: u128,
+    pub refundable: u128,
+    pub remaining_to_fund: u128,
 }
 
 #[contracttype]
@@ -40,27 +207,148 @@ pub struct AllowanceDataKey {
     pub spender: Address,
 }
 
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UserRole {
+    Client,
+    Freelancer,
+    Both,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct UserProfile {
+    // Salted hash of the off-chain profile (name, email, ...) and where to fetch it
+    pub profile_hash: BytesN<32>,
+    pub profile_uri: String,
+    pub role: UserRole,
+    pub skills: Vec<Symbol>,
+    pub hourly_rate: u128,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct User {
     pub id: u64,
     pub user: Address,
-    pub name: String,
-    pub email: String,
+    pub handle: String,
+    pub profile: UserProfile,
     pub registered: bool,
     pub timestamp: u64,
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct RecoveryConfig {
+    pub guardians: Vec<Address>,
+    pub threshold: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Rating {
+    pub rater: Address,
+    pub ratee: Address,
+    pub score: u32,
+    pub comment_hash: BytesN<32>,
+    pub timestamp: u64,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Reputation {
+    pub rating_count: u32,
+    pub rating_total: u32,
+    // Average score scaled by 100, e.g. 450 is 4.5 stars
+    pub average_rating: u32,
+    pub completed_projects: u32,
+    pub completed_volume: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OrgRole {
+    Owner,
+    Approver,
+    Viewer,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TemplateObjective {
+    pub name: Symbol,
+    pub price: u128,
+}
+
+// Reusable objective structure and settings owned by a client
+#[derive(Clone)]
+#[contracttype]
+pub struct Template {
+    pub id: u64,
+    pub owner: Address,
+    pub name: String,
+    pub objectives: Vec<TemplateObjective>,
+    pub delegates: Map<Address, DelegatePermissions>,
+    pub terms: ProjectTerms,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Organization {
+    pub id: u64,
+    pub account: Address,
+    pub members: Map<Address, OrgRole>,
+    pub threshold: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum OrgAction {
+    FundObjective(u128),
+    CompleteObjective(u128),
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
     Project(u128),
+    Timesheet(u128, u32),
+    Settlement(u128),
+    Withdrawal(u128),
+    Holdback(u128, u128),
+    ClaimSettlement(u128, u128),
+    Delegate(u128, Address),
+    Funder(u128, Address),
+    Contributions(u128, u128),
+    Crowdfund(u128, u128),
     Balance(Address),
     Allowance(AllowanceDataKey),
     Admin,
+    RequireRegistration,
 
     // User storage
     User(Address),
     UserRegId(Address),
     UserCounter,
+    UserId(u64),
+    Handle(String),
+    BlockedUser(Address),
+    Recovery(Address),
+    RecoveryRequest(Address, Address),
+    RecoveryCandidates(Address),
+    Rating(u128, Address),
+    OrgCounter,
+    Organization(u64),
+    OrgAccount(Address),
+    OrgApprovals(u128, OrgAction),
+    Reputation(Address),
+
+    // Payouts and refunds waiting to be withdrawn, per token
+    Claimable(Address),
+
+    TemplateCounter,
+    Template(u64),
+    OwnerTemplates(Address),
+    AddressProjects(Address),
+    MemberOrgs(Address),
 }
\ No newline at end of file
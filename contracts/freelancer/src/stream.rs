@@ -0,0 +1,22 @@
+use crate::storage_types::Stream;
+
+// Amount of `price` vested at `now`, linearly between start_time and end_time.
+// Vesting stops at the cancellation time if the stream was cancelled.
+pub fn vested_amount(stream: &Stream, price: u128, now: u64) -> u128 {
+    let now = match stream.cancelled_at {
+        Some(cancelled_at) if cancelled_at < now => cancelled_at,
+        _ => now,
+    };
+
+    if now <= stream.start_time {
+        return 0;
+    }
+
+    if now >= stream.end_time {
+        return price;
+    }
+
+    let elapsed = (now - stream.start_time) as u128;
+    let duration = (stream.end_time - stream.start_time) as u128;
+    price * elapsed / duration
+}
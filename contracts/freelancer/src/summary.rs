@@ -0,0 +1,149 @@
+use soroban_sdk::{Env, Map};
+
+use crate::storage_types::{Objective, ObjectiveKind, Project, ProjectKind, ProjectStatus, ProjectSummary};
+use crate::crowdfund::{raised_amount, remove_crowdfund};
+use crate::holdback::{held_back_amount, read_holdback, remove_holdback};
+use crate::stream::vested_amount;
+
+pub fn project_status(project: &Project) -> ProjectStatus {
+    if project.terminated {
+        return ProjectStatus::Terminated;
+    }
+
+    if project.withdrawn {
+        return ProjectStatus::Withdrawn;
+    }
+
+    if project.cancelled {
+        return ProjectStatus::Cancelled;
+    }
+
+    if project.completed {
+        return ProjectStatus::Completed;
+    }
+
+    match &project.kind {
+        ProjectKind::Retainer(retainer) if retainer.paused => ProjectStatus::Paused,
+        _ => ProjectStatus::Active,
+    }
+}
+
+// Funds of the objective still held by the contract
+pub fn escrowed_amount(objective: &Objective) -> u128 {
+    match &objective.kind {
+        ObjectiveKind::Stream(stream) => objective.half_paid - stream.withdrawn,
+        ObjectiveKind::Milestone if objective.completed => 0,
+        ObjectiveKind::Milestone => objective.half_paid,
+    }
+}
+
+// Part of the escrow that goes back to the client if the objective is dropped now.
+// Vested stream funds belong to the freelancer.
+pub fn refundable_amount(objective: &Objective, now: u64) -> u128 {
+    match &objective.kind {
+        ObjectiveKind::Stream(stream) => {
+            let vested = vested_amount(stream, objective.price, now);
+            objective.half_paid.saturating_sub(vested)
+        }
+        ObjectiveKind::Milestone => escrowed_amount(objective),
+    }
+}
+
+// What the client still has to put in before the objective is fully paid
+pub fn remaining_to_fund(objective: &Objective) -> u128 {
+    match &objective.kind {
+        ObjectiveKind::Stream(stream) if stream.cancelled_at.is_some() => 0,
+        _ if objective.completed => 0,
+        _ => objective.price - objective.half_paid,
+    }
+}
+
+pub fn project_summary(e: &Env, project_id: u128, project: &Project) -> ProjectSummary {
+    let now = e.ledger().timestamp();
+    let status = project_status(project);
+
+    let mut summary = ProjectSummary {
+        status,
+        total_price: 0,
+        escrowed: 0,
+        paid: project.earned_amount,
+        refundable: 0,
+        remaining_to_fund: 0,
+    };
+
+    match &project.kind {
+        // Retainers are charged straight from the client, nothing is escrowed
+        ProjectKind::Retainer(_) => {
+            summary.total_price = project.earned_amount;
+        }
+        // Hourly projects have a single escrow balance instead of objectives
+        ProjectKind::Hourly(_) => {
+            summary.total_price = project.earned_amount + project.contract_balance;
+            summary.escrowed = project.contract_balance;
+            summary.refundable = project.contract_balance;
+        }
+        ProjectKind::Milestones => {
+            for (objective_id, objective) in project.objectives.iter() {
+                // Contributions to a crowdfunded objective are escrowed before it is funded
+                let raised = raised_amount(e, project_id, objective_id);
+
+                summary.total_price += objective.price;
+                summary.escrowed += escrowed_amount(&objective) + raised;
+                summary.refundable += refundable_amount(&objective, now) + raised;
+                if !project.cancelled {
+                    summary.remaining_to_fund += remaining_to_fund(&objective) - raised;
+                }
+            }
+
+            // Holdbacks are still in escrow but already belong to completed objectives
+            summary.escrowed += held_back_amount(e, project_id, project);
+        }
+    }
+
+    summary
+}
+
+// Empties the escrow of every objective still holding funds and returns what each one released.
+// Streams stop vesting, whatever they still held is released, and so are holdbacks.
+// The hourly escrow balance is not tied to an objective and is left to the caller.
+pub fn release_escrow(e: &Env, project_id: u128, project: &mut Project, now: u64) -> Map<u128, u128> {
+    let mut released: Map<u128, u128> = Map::new(e);
+
+    for (objective_id, mut objective) in project.objectives.clone().iter() {
+        let mut amount = raised_amount(e, project_id, objective_id);
+        remove_crowdfund(e, project_id, objective_id);
+
+        if let Some(holdback) = read_holdback(e, project_id, objective_id) {
+            amount += holdback.amount;
+            remove_holdback(e, project_id, objective_id);
+        }
+
+        let escrowed = escrowed_amount(&objective);
+        if amount + escrowed > 0 {
+            released.set(objective_id, amount + escrowed);
+        }
+
+        if escrowed == 0 {
+            continue;
+        }
+
+        objective.half_paid -= escrowed;
+
+        // Vested but unwithdrawn funds are part of the total, nothing is left to withdraw
+        if let ObjectiveKind::Stream(mut stream) = objective.kind.clone() {
+            if stream.cancelled_at.is_none() {
+                stream.cancelled_at = Some(now);
+            }
+            objective.kind = ObjectiveKind::Stream(stream);
+
+            if !objective.completed {
+                objective.completed = true;
+                project.completed_objectives += 1;
+            }
+        }
+
+        project.objectives.set(objective_id, objective);
+    }
+
+    released
+}
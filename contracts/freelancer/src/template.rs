@@ -0,0 +1,54 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::storage::{read_persistent, write_persistent};
+use crate::storage_types::{DataKey, Template};
+
+// Scale is a percentage of the template prices, 100 keeps them unchanged
+pub const SCALE_BASE: u128 = 100;
+
+pub fn read_template(e: &Env, template_id: u64) -> Template {
+    match read_persistent(e, &DataKey::Template(template_id)) {
+        Some(template) => template,
+        None => panic!("Template not found"),
+    }
+}
+
+pub fn write_template(e: &Env, template: &Template) {
+    write_persistent(e, &DataKey::Template(template.id), template);
+}
+
+pub fn read_owner_templates(e: &Env, owner: &Address) -> Vec<u64> {
+    read_persistent(e, &DataKey::OwnerTemplates(owner.clone()))
+        .unwrap_or(Vec::new(e))
+}
+
+pub fn write_owner_templates(e: &Env, owner: &Address, template_ids: &Vec<u64>) {
+    let key = DataKey::OwnerTemplates(owner.clone());
+    if template_ids.is_empty() {
+        e.storage().persistent().remove(&key);
+    } else {
+        write_persistent(e, &key, template_ids);
+    }
+}
+
+pub fn scaled_price(price: u128, scale: u32) -> u128 {
+    price * scale as u128 / SCALE_BASE
+}
+
+pub fn move_templates(e: &Env, old: &Address, new: &Address) {
+    let moved = read_owner_templates(e, old);
+    if moved.is_empty() {
+        return;
+    }
+
+    let mut template_ids = read_owner_templates(e, new);
+    for template_id in moved.iter() {
+        let mut template = read_template(e, template_id);
+        template.owner = new.clone();
+        write_template(e, &template);
+        template_ids.push_back(template_id);
+    }
+
+    write_owner_templates(e, new, &template_ids);
+    write_owner_templates(e, old, &Vec::new(e));
+}
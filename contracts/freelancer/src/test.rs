@@ -2,9 +2,10 @@
 
 extern crate std;
 
-use crate::storage_types::{Project, DataKey};
+use crate::storage_types::{Project, ProjectKind, ProjectStatus, ProjectTerms, ProjectSummary, DataKey, DelegatePermissions, OrgAction, OrgRole, TemplateObjective, TimesheetStatus, UserProfile, UserRole, WithdrawalReason};
 use crate::{contract::FreelanceContract, FreelanceContractClient};
-use soroban_sdk::{testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation}, Address, Env, Vec, IntoVal, symbol_short};
+use crate::events::{ObjectiveEvent, EVENT_VERSION};
+use soroban_sdk::{testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events, Ledger, MockAuth, MockAuthInvoke}, Address, BytesN, Env, Map, String, Symbol, Val, Vec, IntoVal, TryFromVal, symbol_short};
 use crate::token::{ Token, TokenClient };
 
 fn create_token<'a>(e: &Env, admin: &Address) -> TokenClient<'a> {
@@ -13,7 +14,29 @@ fn create_token<'a>(e: &Env, admin: &Address) -> TokenClient<'a> {
     token
 }
 
+// Asserts the last call was authorized by `address` alone, for exactly `function` and `args`
+fn assert_authorized_by(e: &Env, address: &Address, contract: &Address, function: &str, args: Vec<Val>) {
+    let auths = e.auths();
+    assert_eq!(auths.len(), 1);
+
+    let (authorizer, invocation) = &auths[0];
+    assert_eq!(authorizer, address);
+    assert_eq!(
+        invocation.function,
+        AuthorizedFunction::Contract((contract.clone(), Symbol::new(e, function), args))
+    );
+}
+
+// Mocks a signature from `address` alone, for exactly `function` and `args`
+fn mock_auth_by(e: &Env, address: &Address, contract: &Address, function: &str, args: Vec<Val>) {
+    e.mock_auths(&[MockAuth {
+        address,
+        invoke: &MockAuthInvoke { contract, fn_name: function, args, sub_invokes: &[] },
+    }]);
+}
+
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn test_create_fund_complete_objectives() {
     let env = Env::default();
     env.mock_all_auths();
@@ -51,16 +74,19 @@ fn test_create_fund_complete_objectives() {
     assert_eq!(token.allowance(&client_address, &freelance_contract_address), 100);
 
     let prices: Vec<u128> = Vec::from_array(&env, [100_u128, 100_u128]);
-    let project_id = freelance_client.create_project(&freelancer_address, &prices, &client_address);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
 
-    freelance_client.fund_objective(&project_id, &0, &client_address, &usdc_contract_address, &freelance_contract_address);
+    freelance_client.fund_objective(&project_id, &0);
+    // Funding only spends what it needs from the allowance
+    assert_eq!(token.allowance(&client_address, &freelance_contract_address), 50);
     env.as_contract(&freelance_contract_address, || {
         let project_key = DataKey::Project(project_id);
         let project: Project = env.storage().instance().get(&project_key).unwrap();
         let first_objective = project.objectives.get(0).unwrap();
         assert_eq!(first_objective.half_paid, 50);
     });
-    freelance_client.complete_objective(&project_id, &0, &client_address, &usdc_contract_address, &freelance_contract_address, &freelancer_address);
+    freelance_client.complete_objective(&project_id, &0, &client_address);
+    assert_eq!(token.allowance(&client_address, &freelance_contract_address), 0);
     
     env.as_contract(&freelance_contract_address, || {
         let project_key = DataKey::Project(project_id);
@@ -70,8 +96,8 @@ fn test_create_fund_complete_objectives() {
     });
     
     token.approve(&client_address, &freelance_contract_address, &full_price, &expiration_ledger);
-    freelance_client.fund_objective(&project_id, &1, &client_address, &usdc_contract_address, &freelance_contract_address);
-    freelance_client.complete_objective(&project_id, &1, &client_address, &usdc_contract_address, &freelance_contract_address, &freelancer_address);
+    freelance_client.fund_objective(&project_id, &1);
+    freelance_client.complete_objective(&project_id, &1, &client_address);
     
     env.as_contract(&freelance_contract_address, || {
         let project_key = DataKey::Project(project_id);
@@ -117,10 +143,10 @@ fn test_client_can_recover_funds_if_freelancer_does_not_complete_all_objectives(
     let full_price = 100;
 
     let prices: Vec<u128> = Vec::from_array(&env, [100_u128, 100_u128, 100_u128]);
-    let project_id = freelance_client.create_project(&freelancer_address, &prices, &client_address);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
 
     token.approve(&client_address, &freelance_contract_address, &full_price, &expiration_ledger);
-    freelance_client.fund_objective(&project_id, &0, &client_address, &usdc_contract_address, &freelance_contract_address);
+    freelance_client.fund_objective(&project_id, &0);
     env.as_contract(&freelance_contract_address, || {
         let project_key = DataKey::Project(project_id);
         let project: Project = env.storage().instance().get(&project_key).unwrap();
@@ -128,24 +154,30 @@ fn test_client_can_recover_funds_if_freelancer_does_not_complete_all_objectives(
         assert_eq!(first_objective.half_paid, 50);
     });
 
-    freelance_client.complete_objective(&project_id, &0, &client_address, &usdc_contract_address, &freelance_contract_address, &freelancer_address);
+    freelance_client.complete_objective(&project_id, &0, &client_address);
 
     token.approve(&client_address, &freelance_contract_address, &full_price, &expiration_ledger);
-    freelance_client.fund_objective(&project_id, &1, &client_address, &usdc_contract_address, &freelance_contract_address);
+    freelance_client.fund_objective(&project_id, &1);
 
-    freelance_client.complete_objective(&project_id, &1, &client_address, &usdc_contract_address, &freelance_contract_address, &freelancer_address);
+    freelance_client.complete_objective(&project_id, &1, &client_address);
 
     token.approve(&client_address, &freelance_contract_address, &full_price, &expiration_ledger);
-    freelance_client.fund_objective(&project_id, &2, &client_address, &usdc_contract_address, &freelance_contract_address);
+    freelance_client.fund_objective(&project_id, &2);
 
-    freelance_client.cancel_project(&project_id, &client_address);
+    // The funded objective goes back to the client on cancel, payouts wait in escrow until withdrawn
+    freelance_client.cancel_project(&project_id);
 
     env.as_contract(&freelance_contract_address, || {
         let balance = token.balance(&freelance_contract_address);
-        assert_eq!(balance, 50);
+        assert_eq!(balance, 250);
     });
-    
-    freelance_client.refund_remaining_funds(&project_id, &2, &client_address, &usdc_contract_address, &freelance_contract_address);
+
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), Some(50));
+    assert_eq!(freelance_client.claimable(&freelancer_address).get(usdc_contract_address.clone()), Some(200));
+
+    assert_eq!(freelance_client.withdraw(&client_address, &usdc_contract_address), 50);
+    assert_eq!(freelance_client.withdraw(&freelancer_address, &usdc_contract_address), 200);
+    assert!(freelance_client.claimable(&client_address).is_empty());
 
     env.as_contract(&freelance_contract_address, || {
         let balance = token.balance(&freelance_contract_address);
@@ -195,10 +227,10 @@ fn test_add_new_objectives_and_complete_them() {
     let full_price = 100;
 
     let prices: Vec<u128> = Vec::from_array(&env, [100_u128, 100_u128]);
-    let project_id = freelance_client.create_project(&freelancer_address, &prices, &client_address);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
 
     token.approve(&client_address, &freelance_contract_address, &full_price, &expiration_ledger);
-    freelance_client.fund_objective(&project_id, &0, &client_address, &usdc_contract_address, &freelance_contract_address);
+    freelance_client.fund_objective(&project_id, &0);
     env.as_contract(&freelance_contract_address, || {
         let project_key = DataKey::Project(project_id);
         let project: Project = env.storage().instance().get(&project_key).unwrap();
@@ -206,20 +238,21 @@ fn test_add_new_objectives_and_complete_them() {
         assert_eq!(first_objective.half_paid, 50);
     });
 
-    freelance_client.complete_objective(&project_id, &0, &client_address, &usdc_contract_address, &freelance_contract_address, &freelancer_address);
+    freelance_client.complete_objective(&project_id, &0, &client_address);
 
     token.approve(&client_address, &freelance_contract_address, &full_price, &expiration_ledger);
-    freelance_client.fund_objective(&project_id, &1, &client_address, &usdc_contract_address, &freelance_contract_address);
+    freelance_client.fund_objective(&project_id, &1);
 
-    freelance_client.complete_objective(&project_id, &1, &client_address, &usdc_contract_address, &freelance_contract_address, &freelancer_address);
+    freelance_client.complete_objective(&project_id, &1, &client_address);
 
     let new_prices: Vec<u128> = Vec::from_array(&env, [100_u128]);
     freelance_client.add_objective(&project_id, &new_prices, &client_address);
 
     token.approve(&client_address, &freelance_contract_address, &full_price, &expiration_ledger);
-    freelance_client.fund_objective(&project_id, &2, &client_address, &usdc_contract_address, &freelance_contract_address);
+    freelance_client.fund_objective(&project_id, &2);
 
-    freelance_client.complete_objective(&project_id, &2, &client_address, &usdc_contract_address, &freelance_contract_address, &freelancer_address);
+    freelance_client.complete_objective(&project_id, &2, &client_address);
+    freelance_client.withdraw(&freelancer_address, &usdc_contract_address);
 
     let client_balance = token.balance(&client_address);
     let freelancer_balance = token.balance(&freelancer_address);
@@ -229,6 +262,7 @@ fn test_add_new_objectives_and_complete_them() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn test_complete_project_after_all_objectives_completed() {
     let env = Env::default();
     env.mock_all_auths();
@@ -264,10 +298,10 @@ fn test_complete_project_after_all_objectives_completed() {
     let full_price = 100;
 
     let prices: Vec<u128> = Vec::from_array(&env, [100_u128, 100_u128]);
-    let project_id = freelance_client.create_project(&freelancer_address, &prices, &client_address);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
 
     token.approve(&client_address, &freelance_contract_address, &full_price, &expiration_ledger);
-    freelance_client.fund_objective(&project_id, &0, &client_address, &usdc_contract_address, &freelance_contract_address);
+    freelance_client.fund_objective(&project_id, &0);
     env.as_contract(&freelance_contract_address, || {
         let project_key = DataKey::Project(project_id);
         let project: Project = env.storage().instance().get(&project_key).unwrap();
@@ -275,14 +309,14 @@ fn test_complete_project_after_all_objectives_completed() {
         assert_eq!(first_objective.half_paid, 50);
     });
 
-    freelance_client.complete_objective(&project_id, &0, &client_address, &usdc_contract_address, &freelance_contract_address, &freelancer_address);
+    freelance_client.complete_objective(&project_id, &0, &client_address);
 
     token.approve(&client_address, &freelance_contract_address, &full_price, &expiration_ledger);
-    freelance_client.fund_objective(&project_id, &1, &client_address, &usdc_contract_address, &freelance_contract_address);
+    freelance_client.fund_objective(&project_id, &1);
 
-    freelance_client.complete_objective(&project_id, &1, &client_address, &usdc_contract_address, &freelance_contract_address, &freelancer_address);
+    freelance_client.complete_objective(&project_id, &1, &client_address);
 
-    freelance_client.complete_project(&project_id, &client_address);
+    freelance_client.complete_project(&project_id);
 
     env.as_contract(&freelance_contract_address, || {
         let project_key = DataKey::Project(project_id);
@@ -324,8 +358,8 @@ fn test_get_projects_by_freelancer() {
     assert_eq!(token.balance(&client_address), 1000);
 
     let prices: Vec<u128> = Vec::from_array(&env, [100_u128, 100_u128]);
-    freelance_client.create_project(&freelancer_address, &prices, &client_address);
-    freelance_client.create_project(&freelancer_address, &prices, &another_client_address);
+    freelance_client.create_project(&freelancer_address, &prices, &token.address, &client_address);
+    freelance_client.create_project(&freelancer_address, &prices, &token.address, &another_client_address);
 
     let projects = freelance_client.get_projects_by_freelancer(&freelancer_address);
 
@@ -365,10 +399,1834 @@ fn test_get_projects_by_client() {
     assert_eq!(token.balance(&client_address), 1000);
 
     let prices: Vec<u128> = Vec::from_array(&env, [100_u128, 100_u128]);
-    freelance_client.create_project(&freelancer_address, &prices, &client_address);
-    freelance_client.create_project(&another_freelancer_address, &prices, &client_address);
+    freelance_client.create_project(&freelancer_address, &prices, &token.address, &client_address);
+    freelance_client.create_project(&another_freelancer_address, &prices, &token.address, &client_address);
 
     let projects = freelance_client.get_projects_by_client(&client_address);
 
     assert_eq!(projects.len(), 2);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_stream_vests_linearly_and_client_can_cancel() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+
+    env.ledger().with_mut(|li| li.timestamp = 500);
+    let objective_id = freelance_client.add_stream_objective(&project_id, &400, &1000, &2000, &client_address);
+    assert_eq!(objective_id, 1);
+
+    token.approve(&client_address, &freelance_contract_address, &400, &expiration_ledger);
+    freelance_client.fund_objective(&project_id, &objective_id);
+    assert_eq!(token.balance(&freelance_contract_address), 400);
+
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    let withdrawn = freelance_client.withdraw_vested(&project_id, &objective_id, &freelancer_address);
+    assert_eq!(withdrawn, 200);
+
+    env.ledger().with_mut(|li| li.timestamp = 1750);
+    let refunded = freelance_client.cancel_stream(&project_id, &objective_id);
+    assert_eq!(refunded, 100);
+
+    env.ledger().with_mut(|li| li.timestamp = 3000);
+    let withdrawn = freelance_client.withdraw_vested(&project_id, &objective_id, &freelancer_address);
+    assert_eq!(withdrawn, 100);
+
+    env.as_contract(&freelance_contract_address, || {
+        let project_key = DataKey::Project(project_id);
+        let project: Project = env.storage().instance().get(&project_key).unwrap();
+        let stream_objective = project.objectives.get(objective_id).unwrap();
+        assert!(stream_objective.completed);
+        assert_eq!(project.earned_amount, 300);
+    });
+
+    freelance_client.withdraw(&client_address, &usdc_contract_address);
+    freelance_client.withdraw(&freelancer_address, &usdc_contract_address);
+
+    assert_eq!(token.balance(&freelance_contract_address), 0);
+    assert_eq!(token.balance(&client_address), 700);
+    assert_eq!(token.balance(&freelancer_address), 300);
+}
+
+#[test]
+fn test_retainer_charges_each_period_until_paused_or_cancelled() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    token.approve(&client_address, &freelance_contract_address, &300, &expiration_ledger);
+
+    let project_id = freelance_client.create_retainer(&freelancer_address, &100, &1000, &usdc_contract_address, &client_address);
+
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    freelance_client.charge_period(&project_id);
+    assert_eq!(freelance_client.claimable(&freelancer_address).get(usdc_contract_address.clone()), Some(100));
+
+    // Time spent paused is not billed, the next period starts on resume
+    env.ledger().with_mut(|li| li.timestamp = 1200);
+    freelance_client.pause_retainer(&project_id);
+    env.ledger().with_mut(|li| li.timestamp = 2500);
+    freelance_client.resume_retainer(&project_id);
+
+    env.as_contract(&freelance_contract_address, || {
+        let project_key = DataKey::Project(project_id);
+        let project: Project = env.storage().instance().get(&project_key).unwrap();
+        match project.kind {
+            ProjectKind::Retainer(retainer) => {
+                assert_eq!(retainer.next_charge, 3500);
+                assert_eq!(retainer.periods_charged, 1);
+            }
+            _ => panic!("Project should be a retainer"),
+        }
+    });
+
+    env.ledger().with_mut(|li| li.timestamp = 3500);
+    freelance_client.charge_period(&project_id);
+    assert_eq!(freelance_client.withdraw(&freelancer_address, &usdc_contract_address), 200);
+    assert_eq!(token.balance(&freelancer_address), 200);
+
+    freelance_client.cancel_project(&project_id);
+
+    env.as_contract(&freelance_contract_address, || {
+        let project_key = DataKey::Project(project_id);
+        let project: Project = env.storage().instance().get(&project_key).unwrap();
+        assert_eq!(project.earned_amount, 200);
+        assert!(project.cancelled);
+    });
+
+    assert_eq!(token.balance(&client_address), 800);
+    assert_eq!(token.allowance(&client_address, &freelance_contract_address), 100);
+
+    // Completing a retainer stops the charges and counts towards reputation
+    let completed_id = freelance_client.create_retainer(&freelancer_address, &100, &1000, &usdc_contract_address, &client_address);
+    env.ledger().with_mut(|li| li.timestamp = 4500);
+    freelance_client.charge_period(&completed_id);
+    freelance_client.complete_project(&completed_id);
+
+    env.ledger().with_mut(|li| li.timestamp = 5500);
+    assert!(freelance_client.try_charge_period(&completed_id).is_err());
+    assert_eq!(token.balance(&client_address), 700);
+
+    let reputation = freelance_client.get_reputation(&freelancer_address);
+    assert_eq!(reputation.completed_projects, 1);
+    assert_eq!(reputation.completed_volume, 100);
+}
+
+#[test]
+fn test_hourly_project_pays_approved_weeks() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+
+    let project_id = freelance_client.create_hourly_project(&freelancer_address, &10, &40, &usdc_contract_address, &client_address);
+
+    token.approve(&client_address, &freelance_contract_address, &600, &expiration_ledger);
+    freelance_client.fund_hourly(&project_id, &600);
+    assert_eq!(token.balance(&freelance_contract_address), 600);
+
+    let description_hash = BytesN::from_array(&env, &[1; 32]);
+    freelance_client.submit_time_entry(&project_id, &0, &20, &description_hash, &freelancer_address);
+    freelance_client.submit_time_entry(&project_id, &0, &15, &description_hash, &freelancer_address);
+
+    env.ledger().with_mut(|li| li.timestamp = 8 * 24 * 60 * 60);
+    freelance_client.submit_time_entry(&project_id, &1, &10, &description_hash, &freelancer_address);
+
+    let paid = freelance_client.approve_week(&project_id, &0, &client_address);
+    assert_eq!(paid, 350);
+
+    freelance_client.dispute_week(&project_id, &1, &client_address);
+    let disputed_week = freelance_client.get_timesheet(&project_id, &1);
+    assert_eq!(disputed_week.status, TimesheetStatus::Disputed);
+    assert_eq!(disputed_week.hours, 10);
+
+    let approved_week = freelance_client.get_timesheet(&project_id, &0);
+    assert_eq!(approved_week.status, TimesheetStatus::Approved);
+    assert_eq!(approved_week.entries.len(), 2);
+
+    env.as_contract(&freelance_contract_address, || {
+        let project_key = DataKey::Project(project_id);
+        let project: Project = env.storage().instance().get(&project_key).unwrap();
+        assert_eq!(project.earned_amount, 350);
+        assert_eq!(project.contract_balance, 250);
+    });
+
+    assert_eq!(token.balance(&freelance_contract_address), 600);
+    assert_eq!(freelance_client.withdraw(&freelancer_address, &usdc_contract_address), 350);
+    assert_eq!(token.balance(&freelancer_address), 350);
+    assert_eq!(token.balance(&freelance_contract_address), 250);
+
+    // The unspent escrow goes back to the client when the project is completed
+    freelance_client.complete_project(&project_id);
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), Some(250));
+    assert_eq!(freelance_client.withdraw(&client_address, &usdc_contract_address), 250);
+    assert_eq!(token.balance(&freelance_contract_address), 0);
+}
+
+#[test]
+fn test_parties_rate_each_other_and_build_reputation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+
+    token.approve(&client_address, &freelance_contract_address, &100, &expiration_ledger);
+    freelance_client.fund_objective(&project_id, &0);
+    freelance_client.complete_objective(&project_id, &0, &client_address);
+    freelance_client.complete_project(&project_id);
+
+    let comment_hash = BytesN::from_array(&env, &[7; 32]);
+    freelance_client.rate_project(&project_id, &5, &comment_hash, &client_address);
+    freelance_client.rate_project(&project_id, &4, &comment_hash, &freelancer_address);
+
+    assert!(freelance_client.try_rate_project(&project_id, &1, &comment_hash, &client_address).is_err());
+
+    let two_prices: Vec<u128> = Vec::from_array(&env, [100_u128, 100_u128]);
+    let cancelled_project_id = freelance_client.create_project(&freelancer_address, &two_prices, &usdc_contract_address, &client_address);
+    token.approve(&client_address, &freelance_contract_address, &100, &expiration_ledger);
+    freelance_client.fund_objective(&cancelled_project_id, &0);
+    freelance_client.complete_objective(&cancelled_project_id, &0, &client_address);
+    freelance_client.cancel_project(&cancelled_project_id);
+    freelance_client.rate_project(&cancelled_project_id, &2, &comment_hash, &client_address);
+
+    // Funding and cancelling costs nothing once refunded, so it does not allow rating
+    let refunded_project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+    token.approve(&client_address, &freelance_contract_address, &50, &expiration_ledger);
+    freelance_client.fund_objective(&refunded_project_id, &0);
+    freelance_client.cancel_project(&refunded_project_id);
+    assert!(freelance_client.try_rate_project(&refunded_project_id, &1, &comment_hash, &client_address).is_err());
+
+    let unfunded_project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+    freelance_client.cancel_project(&unfunded_project_id);
+    assert!(freelance_client.try_rate_project(&unfunded_project_id, &1, &comment_hash, &client_address).is_err());
+
+    let freelancer_reputation = freelance_client.get_reputation(&freelancer_address);
+    assert_eq!(freelancer_reputation.rating_count, 2);
+    assert_eq!(freelancer_reputation.average_rating, 350);
+    assert_eq!(freelancer_reputation.completed_projects, 1);
+    assert_eq!(freelancer_reputation.completed_volume, 100);
+
+    let client_reputation = freelance_client.get_reputation(&client_address);
+    assert_eq!(client_reputation.rating_count, 1);
+    assert_eq!(client_reputation.average_rating, 400);
+    assert_eq!(client_reputation.completed_projects, 1);
+
+    let rating = freelance_client.get_rating(&project_id, &freelancer_address).unwrap();
+    assert_eq!(rating.ratee, client_address);
+    assert_eq!(rating.score, 4);
+}
+
+#[test]
+fn test_register_update_and_delete_profile() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let user_address = Address::generate(&env);
+    let other_user_address = Address::generate(&env);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    let profile = UserProfile {
+        profile_hash: BytesN::from_array(&env, &[1; 32]),
+        profile_uri: String::from_str(&env, "ipfs://profile-v1"),
+        role: UserRole::Freelancer,
+        skills: Vec::from_array(&env, [symbol_short!("rust"), symbol_short!("soroban")]),
+        hourly_rate: 50,
+    };
+    assert!(!freelance_client.is_registered(&user_address));
+    assert!(freelance_client.get_user(&user_address).is_none());
+
+    let handle = String::from_str(&env, "alice");
+    assert!(freelance_client.register(&user_address, &handle, &profile));
+    assert!(!freelance_client.register(&user_address, &handle, &profile));
+    assert!(freelance_client.is_registered(&user_address));
+
+    // Handles are unique regardless of case
+    assert!(freelance_client.try_register(&other_user_address, &handle, &profile).is_err());
+    assert!(freelance_client.try_register(&other_user_address, &String::from_str(&env, "Alice"), &profile).is_err());
+    assert!(!freelance_client.is_registered(&other_user_address));
+
+    let user = freelance_client.get_user(&user_address).unwrap();
+    assert_eq!(user.id, 1);
+    assert_eq!(user.profile.role, UserRole::Freelancer);
+    assert_eq!(user.profile.skills.len(), 2);
+
+    let updated_profile = UserProfile {
+        profile_hash: BytesN::from_array(&env, &[2; 32]),
+        profile_uri: String::from_str(&env, "ipfs://profile-v2"),
+        role: UserRole::Both,
+        skills: Vec::new(&env),
+        hourly_rate: 65,
+    };
+    freelance_client.update_profile(&user_address, &updated_profile);
+
+    let user = freelance_client.get_user(&user_address).unwrap();
+    assert_eq!(user.profile.profile_hash, updated_profile.profile_hash);
+    assert_eq!(user.profile.profile_uri, updated_profile.profile_uri);
+    assert_eq!(user.profile.role, UserRole::Both);
+    assert_eq!(user.profile.hourly_rate, 65);
+
+    freelance_client.delete_profile(&user_address, &true);
+    let other_handle = String::from_str(&env, "bob");
+    freelance_client.register(&other_user_address, &other_handle, &profile);
+    freelance_client.delete_profile(&other_user_address, &false);
+
+    assert!(!freelance_client.is_registered(&user_address));
+    env.as_contract(&freelance_contract_address, || {
+        assert!(!env.storage().persistent().has(&DataKey::UserRegId(user_address.clone())));
+        assert!(env.storage().persistent().has(&DataKey::BlockedUser(user_address.clone())));
+        assert!(!env.storage().persistent().has(&DataKey::BlockedUser(other_user_address.clone())));
+        assert!(env.storage().persistent().has(&DataKey::Handle(handle.clone())));
+        assert!(!env.storage().persistent().has(&DataKey::Handle(other_handle.clone())));
+    });
+
+    // Without the block the address is free to register again
+    assert!(freelance_client.register(&other_user_address, &other_handle, &profile));
+}
+
+#[test]
+fn test_project_creation_with_registration_required() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    freelance_client.init_admin(&admin);
+    freelance_client.set_require_registration(&true);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    freelance_contract_address.clone(),
+                    Symbol::new(&env, "set_require_registration"),
+                    (true,).into_val(&env),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+    assert!(freelance_client.get_require_registration());
+
+    for (user_address, handle, role) in [(&client_address, "client", UserRole::Client), (&freelancer_address, "freelancer", UserRole::Freelancer)] {
+        let profile = UserProfile {
+            profile_hash: BytesN::from_array(&env, &[1; 32]),
+            profile_uri: String::from_str(&env, "ipfs://profile"),
+            role,
+            skills: Vec::new(&env),
+            hourly_rate: 0,
+        };
+        freelance_client.register(user_address, &String::from_str(&env, handle), &profile);
+    }
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &token, &client_address);
+
+    let (project, client_profile, freelancer_profile) = freelance_client.get_project_with_profiles(&project_id);
+    assert_eq!(project.client, client_address);
+    assert_eq!(client_profile.unwrap().profile.role, UserRole::Client);
+    assert_eq!(freelancer_profile.unwrap().profile.role, UserRole::Freelancer);
+}
+
+#[test]
+fn test_user_directory_lookups() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    let users = [
+        (Address::generate(&env), "Alice_Dev", UserRole::Freelancer),
+        (Address::generate(&env), "bob", UserRole::Client),
+        (Address::generate(&env), "Carol", UserRole::Both),
+        (Address::generate(&env), "dave", UserRole::Freelancer),
+    ];
+
+    for (user_address, handle, role) in users.iter() {
+        let profile = UserProfile {
+            profile_hash: BytesN::from_array(&env, &[1; 32]),
+            profile_uri: String::from_str(&env, "ipfs://profile"),
+            role: *role,
+            skills: Vec::new(&env),
+            hourly_rate: 0,
+        };
+        freelance_client.register(user_address, &String::from_str(&env, handle), &profile);
+    }
+
+    let alice = freelance_client.get_user_by_handle(&String::from_str(&env, "ALICE_dev")).unwrap();
+    assert_eq!(alice.user, users[0].0);
+    assert_eq!(alice.handle, String::from_str(&env, "alice_dev"));
+    assert!(freelance_client.get_user_by_handle(&String::from_str(&env, "nobody")).is_none());
+
+    let carol = freelance_client.get_user_by_id(&3).unwrap();
+    assert_eq!(carol.user, users[2].0);
+
+    assert_eq!(freelance_client.list_users(&None, &1, &10).len(), 4);
+
+    // Users with both roles show up when filtering by either role
+    let freelancers = freelance_client.list_users(&Some(UserRole::Freelancer), &1, &10);
+    assert_eq!(freelancers.len(), 3);
+
+    let first_page = freelance_client.list_users(&Some(UserRole::Freelancer), &1, &2);
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(first_page.get(1).unwrap().id, 3);
+    let second_page = freelance_client.list_users(&Some(UserRole::Freelancer), &4, &2);
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page.get(0).unwrap().user, users[3].0);
+
+    freelance_client.delete_profile(&users[1].0, &false);
+    assert!(freelance_client.get_user_by_id(&2).is_none());
+    assert_eq!(freelance_client.list_users(&Some(UserRole::Client), &1, &10).len(), 1);
+}
+
+#[test]
+fn test_guardians_recover_account_to_new_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let lost_address = Address::generate(&env);
+    let new_address = Address::generate(&env);
+    let admin1 = Address::generate(&env);
+    let guardian_1 = Address::generate(&env);
+    let guardian_2 = Address::generate(&env);
+    let guardian_3 = Address::generate(&env);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    let token = create_token(&env, &admin1);
+    token.mint(&client_address, &1000);
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    token.approve(&client_address, &freelance_contract_address, &100, &expiration_ledger);
+
+    let profile = UserProfile {
+        profile_hash: BytesN::from_array(&env, &[1; 32]),
+        profile_uri: String::from_str(&env, "ipfs://profile"),
+        role: UserRole::Freelancer,
+        skills: Vec::new(&env),
+        hourly_rate: 0,
+    };
+    let handle = String::from_str(&env, "lost_key");
+    freelance_client.register(&lost_address, &handle, &profile);
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128]);
+    let project_id = freelance_client.create_project(&lost_address, &prices, &token.address, &client_address);
+    let completed_project_id = freelance_client.create_project(&lost_address, &prices, &token.address, &client_address);
+    freelance_client.fund_objective(&completed_project_id, &0);
+    freelance_client.complete_objective(&completed_project_id, &0, &client_address);
+    freelance_client.complete_project(&completed_project_id);
+    freelance_client.rate_project(&completed_project_id, &5, &BytesN::from_array(&env, &[0; 32]), &client_address);
+
+    let guardians = Vec::from_array(&env, [guardian_1.clone(), guardian_2.clone(), guardian_3.clone()]);
+    freelance_client.set_recovery(&lost_address, &guardians, &2);
+
+    // A guardian backing another address does not reset the approvals already given,
+    // and switching to a different candidate moves only that guardian's approval
+    let other_address = Address::generate(&env);
+    assert!(!freelance_client.approve_recovery(&lost_address, &new_address, &guardian_1));
+    assert!(!freelance_client.approve_recovery(&lost_address, &other_address, &guardian_2));
+    assert!(freelance_client.try_approve_recovery(&lost_address, &other_address, &guardian_2).is_err());
+    assert!(freelance_client.try_approve_recovery(&lost_address, &new_address, &Address::generate(&env)).is_err());
+    assert!(freelance_client.is_registered(&lost_address));
+    assert!(freelance_client.approve_recovery(&lost_address, &new_address, &guardian_2));
+
+    assert!(!freelance_client.is_registered(&lost_address));
+    let user = freelance_client.get_user(&new_address).unwrap();
+    assert_eq!(user.id, 1);
+    assert_eq!(freelance_client.get_user_by_handle(&handle).unwrap().user, new_address);
+    assert_eq!(freelance_client.get_user_by_id(&1).unwrap().user, new_address);
+    assert_eq!(freelance_client.get_recovery(&new_address).unwrap().threshold, 2);
+
+    assert_eq!(freelance_client.get_reputation(&new_address).rating_count, 1);
+    assert_eq!(freelance_client.get_reputation(&lost_address).rating_count, 0);
+
+    assert_eq!(freelance_client.get_projects_by_freelancer(&new_address).len(), 2);
+    assert_eq!(freelance_client.get_projects_by_freelancer(&lost_address).len(), 0);
+    let (project, _, freelancer_profile) = freelance_client.get_project_with_profiles(&project_id);
+    assert_eq!(project.freelancer, new_address);
+    assert_eq!(freelancer_profile.unwrap().handle, handle);
+
+    // The recovered user can move again with both keys
+    let newest_address = Address::generate(&env);
+    freelance_client.migrate_account(&new_address, &newest_address);
+    assert_eq!(freelance_client.get_projects_by_freelancer(&newest_address).len(), 2);
+}
+
+#[test]
+fn test_organization_client_needs_threshold_approvals() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let org_account = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let viewer = Address::generate(&env);
+    let freelancer_org_account = Address::generate(&env);
+    let freelancer_member = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&org_account, &1000);
+
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+
+    let mut members = Map::new(&env);
+    members.set(owner.clone(), OrgRole::Owner);
+    members.set(viewer.clone(), OrgRole::Viewer);
+    let org_id = freelance_client.create_organization(&org_account, &members, &1);
+    freelance_client.set_org_member(&org_id, &approver, &OrgRole::Approver, &owner);
+    freelance_client.set_org_threshold(&org_id, &2, &owner);
+
+    let org = freelance_client.get_organization_by_account(&org_account).unwrap();
+    assert_eq!(org.id, org_id);
+    assert_eq!(org.threshold, 2);
+    assert_eq!(org.members.len(), 3);
+
+    let mut freelancer_members = Map::new(&env);
+    freelancer_members.set(freelancer_member.clone(), OrgRole::Owner);
+    freelance_client.create_organization(&freelancer_org_account, &freelancer_members, &1);
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128]);
+    let project_id = freelance_client.create_project(&freelancer_org_account, &prices, &usdc_contract_address, &org_account);
+    token.approve(&org_account, &freelance_contract_address, &100, &expiration_ledger);
+
+    let fund = OrgAction::FundObjective(0);
+    assert!(!freelance_client.approve_org_action(&project_id, &fund, &owner));
+    assert_eq!(token.balance(&freelance_contract_address), 0);
+    assert!(freelance_client.approve_org_action(&project_id, &fund, &approver));
+    assert_eq!(token.balance(&freelance_contract_address), 50);
+
+    // A delegate of the organization cannot approve around the threshold
+    let delegate = Address::generate(&env);
+    let permissions = DelegatePermissions { approve_objectives: true, add_objectives: false, review_timesheets: false };
+    freelance_client.set_delegate(&project_id, &delegate, &permissions);
+    assert!(freelance_client.try_complete_objective(&project_id, &0, &delegate).is_err());
+    assert!(freelance_client.try_complete_objectives(&project_id, &Vec::from_array(&env, [0_u128]), &delegate).is_err());
+
+    let complete = OrgAction::CompleteObjective(0);
+    assert!(!freelance_client.approve_org_action(&project_id, &complete, &approver));
+    assert!(freelance_client.approve_org_action(&project_id, &complete, &owner));
+
+    assert_eq!(token.balance(&org_account), 900);
+    freelance_client.withdraw(&freelancer_org_account, &usdc_contract_address);
+    assert_eq!(token.balance(&freelancer_org_account), 100);
+
+    // Members of the freelancer organization act for its account
+    let stream_id = freelance_client.add_stream_objective(&project_id, &10, &0, &10, &org_account);
+    token.approve(&org_account, &freelance_contract_address, &10, &expiration_ledger);
+    freelance_client.approve_org_action(&project_id, &OrgAction::FundObjective(stream_id), &owner);
+    freelance_client.approve_org_action(&project_id, &OrgAction::FundObjective(stream_id), &approver);
+    env.ledger().with_mut(|li| li.timestamp = 10);
+    freelance_client.withdraw_vested(&project_id, &stream_id, &freelancer_member);
+    freelance_client.withdraw(&freelancer_org_account, &usdc_contract_address);
+    assert_eq!(token.balance(&freelancer_org_account), 110);
+}
+
+#[test]
+fn test_delegate_approves_and_adds_objectives_without_paying() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let project_manager = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+
+    let permissions = DelegatePermissions {
+        approve_objectives: true,
+        add_objectives: true,
+        review_timesheets: false,
+    };
+    freelance_client.set_delegate(&project_id, &project_manager, &permissions);
+    assert!(freelance_client.get_delegate(&project_id, &project_manager).unwrap().approve_objectives);
+
+    let new_prices: Vec<u128> = Vec::from_array(&env, [200_u128]);
+    freelance_client.add_objective(&project_id, &new_prices, &project_manager);
+
+    token.approve(&client_address, &freelance_contract_address, &100, &expiration_ledger);
+    freelance_client.fund_objective(&project_id, &0);
+
+    token.approve(&client_address, &freelance_contract_address, &50, &expiration_ledger);
+    freelance_client.complete_objective(&project_id, &0, &project_manager);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            project_manager.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    freelance_contract_address.clone(),
+                    Symbol::new(&env, "complete_objective"),
+                    (project_id, 0_u128, project_manager.clone()).into_val(&env),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+
+    freelance_client.withdraw(&freelancer_address, &usdc_contract_address);
+    assert_eq!(token.balance(&project_manager), 0);
+    assert_eq!(token.balance(&client_address), 900);
+    assert_eq!(token.balance(&freelancer_address), 100);
+
+    env.as_contract(&freelance_contract_address, || {
+        let project_key = DataKey::Project(project_id);
+        let project: Project = env.storage().instance().get(&project_key).unwrap();
+        assert_eq!(project.objectives_count, 2);
+        assert_eq!(project.objectives.get(1).unwrap().price, 200);
+        assert!(project.objectives.get(0).unwrap().completed);
+    });
+
+    freelance_client.remove_delegate(&project_id, &project_manager);
+    assert!(freelance_client.get_delegate(&project_id, &project_manager).is_none());
+}
+
+#[test]
+fn test_events_carry_project_id_parties_and_typed_data() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    token.approve(&client_address, &freelance_contract_address, &100, &expiration_ledger);
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+    freelance_client.fund_objective(&project_id, &0);
+
+    // Skip the token events and look at the last one published by the freelance contract
+    let (_, topics, data) = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract, _, _)| *contract == freelance_contract_address)
+        .last()
+        .unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&env, "objective_funded"), project_id, client_address.clone(), freelancer_address.clone()).into_val(&env)
+    );
+    let event = ObjectiveEvent::try_from_val(&env, &data).unwrap();
+    assert_eq!(event, ObjectiveEvent { version: EVENT_VERSION, objective_id: 0, amount: 50 });
+}
+
+#[test]
+fn test_project_views_and_summary() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128, 200_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+
+    token.approve(&client_address, &freelance_contract_address, &100, &expiration_ledger);
+    freelance_client.fund_objective(&project_id, &0);
+    freelance_client.complete_objective(&project_id, &0, &client_address);
+
+    token.approve(&client_address, &freelance_contract_address, &100, &expiration_ledger);
+    freelance_client.fund_objective(&project_id, &1);
+
+    let project = freelance_client.get_project(&project_id);
+    assert_eq!(project.client, client_address);
+    assert_eq!(project.completed_objectives, 1);
+
+    let objective = freelance_client.get_objective(&project_id, &1);
+    assert_eq!(objective.price, 200);
+    assert_eq!(objective.half_paid, 100);
+    assert!(!objective.completed);
+
+    assert_eq!(
+        freelance_client.get_project_summary(&project_id),
+        ProjectSummary {
+            status: ProjectStatus::Active,
+            total_price: 300,
+            escrowed: 100,
+            paid: 100,
+            refundable: 100,
+            remaining_to_fund: 100,
+        }
+    );
+
+    freelance_client.cancel_project(&project_id);
+
+    let summary = freelance_client.get_project_summary(&project_id);
+    assert_eq!(summary.status, ProjectStatus::Cancelled);
+    assert_eq!(summary.escrowed, 0);
+    assert_eq!(summary.remaining_to_fund, 0);
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), Some(100));
+}
+
+#[test]
+fn test_entry_points_authenticate_the_stored_party() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let project_manager = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+    let contract = freelance_contract_address.clone();
+
+    token.mint(&client_address, &10000);
+    let usdc = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+
+    // Admin
+    let admin = Address::generate(&env);
+    freelance_client.init_admin(&admin);
+    freelance_client.set_require_registration(&false);
+    assert_authorized_by(&env, &admin, &contract, "set_require_registration", (false,).into_val(&env));
+
+    // Milestones
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128, 100_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &token.address, &client_address);
+    assert_authorized_by(&env, &client_address, &contract, "create_project", (freelancer_address.clone(), prices.clone(), usdc.clone(), client_address.clone()).into_val(&env));
+
+    token.approve(&client_address, &contract, &100, &expiration_ledger);
+    freelance_client.fund_objective(&project_id, &0);
+    assert_authorized_by(&env, &client_address, &contract, "fund_objective", (project_id, 0_u128).into_val(&env));
+
+    freelance_client.complete_objective(&project_id, &0, &client_address);
+    assert_authorized_by(&env, &client_address, &contract, "complete_objective", (project_id, 0_u128, client_address.clone()).into_val(&env));
+
+    let permissions = DelegatePermissions { approve_objectives: true, add_objectives: true, review_timesheets: false };
+    freelance_client.set_delegate(&project_id, &project_manager, &permissions);
+    assert_authorized_by(&env, &client_address, &contract, "set_delegate", (project_id, project_manager.clone(), permissions.clone()).into_val(&env));
+
+    freelance_client.remove_delegate(&project_id, &project_manager);
+    assert_authorized_by(&env, &client_address, &contract, "remove_delegate", (project_id, project_manager.clone()).into_val(&env));
+
+    let objective_id = freelance_client.add_stream_objective(&project_id, &400, &1000, &2000, &client_address);
+    assert_authorized_by(&env, &client_address, &contract, "add_stream_objective", (project_id, 400_u128, 1000_u64, 2000_u64, client_address.clone()).into_val(&env));
+
+    token.approve(&client_address, &contract, &400, &expiration_ledger);
+    freelance_client.fund_objective(&project_id, &objective_id);
+
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    freelance_client.withdraw_vested(&project_id, &objective_id, &freelancer_address);
+    assert_authorized_by(&env, &freelancer_address, &contract, "withdraw_vested", (project_id, objective_id, freelancer_address.clone()).into_val(&env));
+
+    freelance_client.cancel_stream(&project_id, &objective_id);
+    assert_authorized_by(&env, &client_address, &contract, "cancel_stream", (project_id, objective_id).into_val(&env));
+
+    token.approve(&client_address, &contract, &100, &expiration_ledger);
+    freelance_client.fund_objective(&project_id, &1);
+
+    freelance_client.cancel_project(&project_id);
+    assert_authorized_by(&env, &client_address, &contract, "cancel_project", (project_id,).into_val(&env));
+
+    let score = 5_u32;
+    let comment_hash = BytesN::from_array(&env, &[2; 32]);
+    freelance_client.rate_project(&project_id, &score, &comment_hash, &freelancer_address);
+    assert_authorized_by(&env, &freelancer_address, &contract, "rate_project", (project_id, score, comment_hash.clone(), freelancer_address.clone()).into_val(&env));
+
+    let single_price: Vec<u128> = Vec::from_array(&env, [100_u128]);
+    let completed_project_id = freelance_client.create_project(&freelancer_address, &single_price, &token.address, &client_address);
+    freelance_client.add_objective(&completed_project_id, &single_price, &client_address);
+    assert_authorized_by(&env, &client_address, &contract, "add_objective", (completed_project_id, single_price.clone(), client_address.clone()).into_val(&env));
+
+    let objective_ids: Vec<u128> = Vec::from_array(&env, [0_u128, 1_u128]);
+    token.approve(&client_address, &contract, &200, &expiration_ledger);
+    freelance_client.fund_objectives(&completed_project_id, &objective_ids);
+    assert_authorized_by(&env, &client_address, &contract, "fund_objectives", (completed_project_id, objective_ids.clone()).into_val(&env));
+
+    freelance_client.complete_objectives(&completed_project_id, &objective_ids, &client_address);
+    assert_authorized_by(&env, &client_address, &contract, "complete_objectives", (completed_project_id, objective_ids.clone(), client_address.clone()).into_val(&env));
+
+    freelance_client.complete_project(&completed_project_id);
+    assert_authorized_by(&env, &client_address, &contract, "complete_project", (completed_project_id,).into_val(&env));
+
+    freelance_client.withdraw(&freelancer_address, &usdc);
+    assert_authorized_by(&env, &freelancer_address, &contract, "withdraw", (freelancer_address.clone(), usdc.clone()).into_val(&env));
+
+    // Retainer
+    let retainer_id = freelance_client.create_retainer(&freelancer_address, &100, &1000, &token.address, &client_address);
+    freelance_client.pause_retainer(&retainer_id);
+    assert_authorized_by(&env, &client_address, &contract, "pause_retainer", (retainer_id,).into_val(&env));
+
+    freelance_client.resume_retainer(&retainer_id);
+    assert_authorized_by(&env, &client_address, &contract, "resume_retainer", (retainer_id,).into_val(&env));
+
+    // Hourly
+    let hourly_id = freelance_client.create_hourly_project(&freelancer_address, &10, &40, &token.address, &client_address);
+    token.approve(&client_address, &contract, &200, &expiration_ledger);
+    freelance_client.fund_hourly(&hourly_id, &200);
+    assert_authorized_by(&env, &client_address, &contract, "fund_hourly", (hourly_id, 200_u128).into_val(&env));
+
+    let description_hash = BytesN::from_array(&env, &[1; 32]);
+    freelance_client.submit_time_entry(&hourly_id, &0, &10, &description_hash, &freelancer_address);
+    assert_authorized_by(&env, &freelancer_address, &contract, "submit_time_entry", (hourly_id, 0_u32, 10_u32, description_hash.clone(), freelancer_address.clone()).into_val(&env));
+
+    freelance_client.approve_week(&hourly_id, &0, &client_address);
+    assert_authorized_by(&env, &client_address, &contract, "approve_week", (hourly_id, 0_u32, client_address.clone()).into_val(&env));
+
+    env.ledger().with_mut(|li| li.timestamp = 1500 + 8 * 24 * 60 * 60);
+    freelance_client.submit_time_entry(&hourly_id, &1, &5, &description_hash, &freelancer_address);
+    freelance_client.dispute_week(&hourly_id, &1, &client_address);
+    assert_authorized_by(&env, &client_address, &contract, "dispute_week", (hourly_id, 1_u32, client_address.clone()).into_val(&env));
+
+    freelance_client.cancel_project(&hourly_id);
+    assert_authorized_by(&env, &client_address, &contract, "cancel_project", (hourly_id,).into_val(&env));
+
+    // Users
+    let guardian = Address::generate(&env);
+    let recovered_address = Address::generate(&env);
+    let new_client_address = Address::generate(&env);
+    let handle = String::from_str(&env, "bob");
+    let profile = UserProfile {
+        profile_hash: BytesN::from_array(&env, &[3; 32]),
+        profile_uri: String::from_str(&env, "ipfs://profile"),
+        role: UserRole::Freelancer,
+        skills: Vec::from_array(&env, [symbol_short!("rust")]),
+        hourly_rate: 10,
+    };
+    freelance_client.register(&freelancer_address, &handle, &profile);
+    assert_authorized_by(&env, &freelancer_address, &contract, "register", (freelancer_address.clone(), handle.clone(), profile.clone()).into_val(&env));
+
+    freelance_client.update_profile(&freelancer_address, &profile);
+    assert_authorized_by(&env, &freelancer_address, &contract, "update_profile", (freelancer_address.clone(), profile.clone()).into_val(&env));
+
+    let guardians: Vec<Address> = Vec::from_array(&env, [guardian.clone()]);
+    freelance_client.set_recovery(&freelancer_address, &guardians, &1);
+    assert_authorized_by(&env, &freelancer_address, &contract, "set_recovery", (freelancer_address.clone(), guardians.clone(), 1_u32).into_val(&env));
+
+    freelance_client.approve_recovery(&freelancer_address, &recovered_address, &guardian);
+    assert_authorized_by(&env, &guardian, &contract, "approve_recovery", (freelancer_address.clone(), recovered_address.clone(), guardian.clone()).into_val(&env));
+
+    freelance_client.delete_profile(&recovered_address, &false);
+    assert_authorized_by(&env, &recovered_address, &contract, "delete_profile", (recovered_address.clone(), false).into_val(&env));
+
+    // Both the old and the new address sign a migration
+    freelance_client.register(&client_address, &String::from_str(&env, "carol"), &profile);
+    freelance_client.migrate_account(&client_address, &new_client_address);
+    let auths = env.auths();
+    assert_eq!(auths.len(), 2);
+    let args: Vec<Val> = (client_address.clone(), new_client_address.clone()).into_val(&env);
+    for (authorizer, address) in auths.iter().zip([&client_address, &new_client_address]) {
+        assert_eq!(authorizer.0, *address);
+        assert_eq!(
+            authorizer.1.function,
+            AuthorizedFunction::Contract((contract.clone(), Symbol::new(&env, "migrate_account"), args.clone()))
+        );
+    }
+
+    // Organizations
+    let org_account = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let members = Map::from_array(&env, [(owner.clone(), OrgRole::Owner)]);
+    let org_id = freelance_client.create_organization(&org_account, &members, &1);
+    assert_authorized_by(&env, &org_account, &contract, "create_organization", (org_account.clone(), members.clone(), 1_u32).into_val(&env));
+
+    freelance_client.set_org_member(&org_id, &approver, &OrgRole::Approver, &owner);
+    assert_authorized_by(&env, &owner, &contract, "set_org_member", (org_id, approver.clone(), OrgRole::Approver, owner.clone()).into_val(&env));
+
+    token.mint(&org_account, &100);
+    token.approve(&org_account, &contract, &100, &expiration_ledger);
+    let org_project_id = freelance_client.create_project(&recovered_address, &single_price, &token.address, &org_account);
+    let fund = OrgAction::FundObjective(0);
+    freelance_client.approve_org_action(&org_project_id, &fund, &approver);
+    assert_authorized_by(&env, &approver, &contract, "approve_org_action", (org_project_id, fund.clone(), approver.clone()).into_val(&env));
+}
+
+#[test]
+fn test_entry_points_reject_other_parties() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let project_manager = Address::generate(&env);
+    let impostor = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+    let contract = freelance_contract_address.clone();
+
+    token.mint(&client_address, &10000);
+    token.approve(&client_address, &contract, &10000, &(env.ledger().sequence() + 1000));
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128, 100_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &token.address, &client_address);
+
+    // Client only entry points need the stored client's signature
+    mock_auth_by(&env, &impostor, &contract, "fund_objective", (project_id, 0_u128).into_val(&env));
+    assert!(freelance_client.try_fund_objective(&project_id, &0).is_err());
+
+    let permissions = DelegatePermissions { approve_objectives: true, add_objectives: true, review_timesheets: true };
+    mock_auth_by(&env, &impostor, &contract, "set_delegate", (project_id, impostor.clone(), permissions.clone()).into_val(&env));
+    assert!(freelance_client.try_set_delegate(&project_id, &impostor, &permissions).is_err());
+
+    mock_auth_by(&env, &impostor, &contract, "cancel_project", (project_id,).into_val(&env));
+    assert!(freelance_client.try_cancel_project(&project_id).is_err());
+
+    mock_auth_by(&env, &impostor, &contract, "complete_project", (project_id,).into_val(&env));
+    assert!(freelance_client.try_complete_project(&project_id).is_err());
+
+    env.mock_all_auths();
+    let project = freelance_client.get_project(&project_id);
+    assert!(!project.cancelled && !project.completed);
+    assert_eq!(project.objectives.get(0).unwrap().half_paid, 0);
+
+    // A delegate can only do what the client granted
+    let permissions = DelegatePermissions { approve_objectives: false, add_objectives: true, review_timesheets: false };
+    freelance_client.set_delegate(&project_id, &project_manager, &permissions);
+    freelance_client.fund_objective(&project_id, &0);
+    assert!(freelance_client.try_complete_objective(&project_id, &0, &project_manager).is_err());
+    assert!(freelance_client.try_complete_objectives(&project_id, &Vec::from_array(&env, [0_u128]), &project_manager).is_err());
+    assert!(freelance_client.try_complete_objective(&project_id, &0, &impostor).is_err());
+    freelance_client.add_objective(&project_id, &Vec::from_array(&env, [50_u128]), &project_manager);
+    assert!(freelance_client.try_add_objective(&project_id, &Vec::from_array(&env, [50_u128]), &impostor).is_err());
+    assert_eq!(freelance_client.get_project(&project_id).objectives.len(), 3);
+
+    // Only the freelancer can withdraw vested funds or leave the project
+    let objective_id = freelance_client.add_stream_objective(&project_id, &400, &1000, &2000, &client_address);
+    freelance_client.fund_objective(&project_id, &objective_id);
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    assert!(freelance_client.try_withdraw_vested(&project_id, &objective_id, &impostor).is_err());
+
+    mock_auth_by(&env, &impostor, &contract, "withdraw_from_project", (project_id, WithdrawalReason::Personal).into_val(&env));
+    assert!(freelance_client.try_withdraw_from_project(&project_id, &WithdrawalReason::Personal).is_err());
+
+    env.mock_all_auths();
+    assert_eq!(freelance_client.withdraw_vested(&project_id, &objective_id, &freelancer_address), 200);
+    assert!(freelance_client.get_withdrawal(&project_id).is_none());
+
+    // Ratings are signed by the party they are written for
+    let comment_hash = BytesN::from_array(&env, &[2; 32]);
+    mock_auth_by(&env, &impostor, &contract, "rate_project", (project_id, 1_u32, comment_hash.clone(), client_address.clone()).into_val(&env));
+    assert!(freelance_client.try_rate_project(&project_id, &1, &comment_hash, &client_address).is_err());
+
+    env.mock_all_auths();
+    assert!(freelance_client.try_rate_project(&project_id, &1, &comment_hash, &impostor).is_err());
+    assert!(freelance_client.get_rating(&project_id, &client_address).is_none());
+
+    // Timesheets are reviewed by the client
+    let hourly_id = freelance_client.create_hourly_project(&freelancer_address, &10, &40, &token.address, &client_address);
+    freelance_client.fund_hourly(&hourly_id, &100);
+    let description_hash = BytesN::from_array(&env, &[1; 32]);
+    freelance_client.submit_time_entry(&hourly_id, &0, &5, &description_hash, &freelancer_address);
+
+    mock_auth_by(&env, &impostor, &contract, "approve_week", (hourly_id, 0_u32, client_address.clone()).into_val(&env));
+    assert!(freelance_client.try_approve_week(&hourly_id, &0, &client_address).is_err());
+
+    mock_auth_by(&env, &impostor, &contract, "dispute_week", (hourly_id, 0_u32, client_address.clone()).into_val(&env));
+    assert!(freelance_client.try_dispute_week(&hourly_id, &0, &client_address).is_err());
+
+    env.mock_all_auths();
+    assert!(freelance_client.try_approve_week(&hourly_id, &0, &impostor).is_err());
+    assert!(freelance_client.try_dispute_week(&hourly_id, &0, &impostor).is_err());
+    assert_eq!(freelance_client.get_timesheet(&hourly_id, &0).status, TimesheetStatus::Pending);
+
+    // Recovery is set up by the user and approved by their guardians only
+    let guardian = Address::generate(&env);
+    let profile = UserProfile {
+        profile_hash: BytesN::from_array(&env, &[3; 32]),
+        profile_uri: String::from_str(&env, "ipfs://profile"),
+        role: UserRole::Freelancer,
+        skills: Vec::from_array(&env, [symbol_short!("rust")]),
+        hourly_rate: 10,
+    };
+    freelance_client.register(&freelancer_address, &String::from_str(&env, "bob"), &profile);
+
+    let impostor_guardians: Vec<Address> = Vec::from_array(&env, [impostor.clone()]);
+    mock_auth_by(&env, &impostor, &contract, "set_recovery", (freelancer_address.clone(), impostor_guardians.clone(), 1_u32).into_val(&env));
+    assert!(freelance_client.try_set_recovery(&freelancer_address, &impostor_guardians, &1).is_err());
+
+    env.mock_all_auths();
+    freelance_client.set_recovery(&freelancer_address, &Vec::from_array(&env, [guardian.clone()]), &1);
+
+    mock_auth_by(&env, &impostor, &contract, "approve_recovery", (freelancer_address.clone(), impostor.clone(), guardian.clone()).into_val(&env));
+    assert!(freelance_client.try_approve_recovery(&freelancer_address, &impostor, &guardian).is_err());
+
+    mock_auth_by(&env, &impostor, &contract, "migrate_account", (freelancer_address.clone(), impostor.clone()).into_val(&env));
+    assert!(freelance_client.try_migrate_account(&freelancer_address, &impostor).is_err());
+
+    env.mock_all_auths();
+    assert!(freelance_client.try_approve_recovery(&freelancer_address, &impostor, &impostor).is_err());
+    assert!(freelance_client.get_user(&freelancer_address).is_some());
+    assert!(freelance_client.get_user(&impostor).is_none());
+
+    // Organizations are created by their account and run by their owners
+    let org_account = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let members = Map::from_array(&env, [(owner.clone(), OrgRole::Owner)]);
+    mock_auth_by(&env, &impostor, &contract, "create_organization", (org_account.clone(), members.clone(), 1_u32).into_val(&env));
+    assert!(freelance_client.try_create_organization(&org_account, &members, &1).is_err());
+
+    env.mock_all_auths();
+    let org_id = freelance_client.create_organization(&org_account, &members, &1);
+
+    mock_auth_by(&env, &impostor, &contract, "set_org_member", (org_id, impostor.clone(), OrgRole::Owner, owner.clone()).into_val(&env));
+    assert!(freelance_client.try_set_org_member(&org_id, &impostor, &OrgRole::Owner, &owner).is_err());
+
+    env.mock_all_auths();
+    assert!(freelance_client.try_set_org_member(&org_id, &impostor, &OrgRole::Owner, &impostor).is_err());
+
+    token.mint(&org_account, &100);
+    token.approve(&org_account, &contract, &100, &(env.ledger().sequence() + 1000));
+    let org_project_id = freelance_client.create_project(&freelancer_address, &Vec::from_array(&env, [100_u128]), &token.address, &org_account);
+    let fund = OrgAction::FundObjective(0);
+    mock_auth_by(&env, &impostor, &contract, "approve_org_action", (org_project_id, fund.clone(), owner.clone()).into_val(&env));
+    assert!(freelance_client.try_approve_org_action(&org_project_id, &fund, &owner).is_err());
+
+    env.mock_all_auths();
+    assert!(freelance_client.try_approve_org_action(&org_project_id, &fund, &impostor).is_err());
+    assert_eq!(freelance_client.get_organization(&org_id).members.len(), 1);
+    assert_eq!(freelance_client.get_project(&org_project_id).objectives.get(0).unwrap().half_paid, 0);
+}
+
+#[test]
+fn test_payouts_are_credited_and_withdrawn_to_a_destination() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let cold_wallet = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128, 300_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+
+    token.approve(&client_address, &freelance_contract_address, &100, &expiration_ledger);
+    freelance_client.fund_objective(&project_id, &0);
+    freelance_client.complete_objective(&project_id, &0, &client_address);
+
+    token.approve(&client_address, &freelance_contract_address, &300, &expiration_ledger);
+    freelance_client.fund_objective(&project_id, &1);
+    freelance_client.complete_objective(&project_id, &1, &client_address);
+
+    // Nothing is pushed to the freelancer, both payouts add up in escrow
+    assert_eq!(token.balance(&freelancer_address), 0);
+    assert_eq!(token.balance(&freelance_contract_address), 400);
+    assert_eq!(freelance_client.claimable(&freelancer_address).get(usdc_contract_address.clone()), Some(400));
+
+    let withdrawn = freelance_client.withdraw_to(&freelancer_address, &usdc_contract_address, &cold_wallet);
+    assert_authorized_by(&env, &freelancer_address, &freelance_contract_address, "withdraw_to", (freelancer_address.clone(), usdc_contract_address.clone(), cold_wallet.clone()).into_val(&env));
+    assert_eq!(withdrawn, 400);
+
+    assert_eq!(token.balance(&cold_wallet), 400);
+    assert_eq!(token.balance(&freelancer_address), 0);
+    assert_eq!(token.balance(&freelance_contract_address), 0);
+    assert!(freelance_client.claimable(&freelancer_address).is_empty());
+}
+
+#[test]
+fn test_batch_funding_and_completion() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    token.approve(&client_address, &freelance_contract_address, &600, &expiration_ledger);
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128, 200_u128, 300_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+
+    let count_events = |contract_address: &Address| {
+        env.events().all().iter().filter(|(contract, _, _)| contract == contract_address).count()
+    };
+    let token_events = count_events(&usdc_contract_address);
+    let contract_events = count_events(&freelance_contract_address);
+
+    let all_ids: Vec<u128> = Vec::from_array(&env, [0_u128, 1_u128, 2_u128]);
+    freelance_client.fund_objectives(&project_id, &all_ids);
+
+    // One transfer for the batch and one event per objective
+    assert_eq!(count_events(&usdc_contract_address), token_events + 1);
+    assert_eq!(count_events(&freelance_contract_address), contract_events + 3);
+    assert_eq!(token.balance(&freelance_contract_address), 300);
+
+    let some_ids: Vec<u128> = Vec::from_array(&env, [0_u128, 2_u128]);
+    freelance_client.complete_objectives(&project_id, &some_ids, &client_address);
+
+    let summary = freelance_client.get_project_summary(&project_id);
+    assert_eq!(summary.paid, 400);
+    assert_eq!(summary.escrowed, 100);
+    assert_eq!(freelance_client.get_project(&project_id).completed_objectives, 2);
+    assert_eq!(freelance_client.claimable(&freelancer_address).get(usdc_contract_address.clone()), Some(400));
+    assert_eq!(token.balance(&client_address), 500);
+}
+
+#[test]
+fn test_projects_created_from_a_template() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let project_manager = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    let objectives = Vec::from_array(&env, [
+        TemplateObjective { name: Symbol::new(&env, "discovery"), price: 100 },
+        TemplateObjective { name: Symbol::new(&env, "design"), price: 200 },
+        TemplateObjective { name: Symbol::new(&env, "build"), price: 500 },
+        TemplateObjective { name: Symbol::new(&env, "qa"), price: 200 },
+    ]);
+    let permissions = DelegatePermissions { approve_objectives: true, add_objectives: false, review_timesheets: false };
+    let mut delegates = Map::new(&env);
+    delegates.set(project_manager.clone(), permissions.clone());
+
+    let name = String::from_str(&env, "Website");
+    let terms = ProjectTerms { kill_fee_percent: 20, ..Default::default() };
+    let template_id = freelance_client.create_template(&client_address, &name, &objectives, &delegates, &terms);
+    assert_eq!(freelance_client.get_template(&template_id).objectives, objectives);
+
+    // Scale is a percentage of the template prices
+    let project_id = freelance_client.create_project_from_template(&template_id, &freelancer_address, &token, &150);
+    assert_authorized_by(&env, &client_address, &freelance_contract_address, "create_project_from_template", (template_id, freelancer_address.clone(), token.clone(), 150_u32).into_val(&env));
+
+    let project = freelance_client.get_project(&project_id);
+    assert_eq!(project.client, client_address);
+    assert_eq!(project.freelancer, freelancer_address);
+    assert_eq!(project.objectives_count, 4);
+    assert_eq!(freelance_client.get_objective(&project_id, &2).price, 750);
+    assert_eq!(freelance_client.get_project_summary(&project_id).total_price, 1500);
+    assert_eq!(freelance_client.get_delegate(&project_id, &project_manager), Some(permissions));
+    assert_eq!(project.terms, terms);
+
+    assert_eq!(freelance_client.get_templates(&client_address).len(), 1);
+    freelance_client.remove_template(&template_id);
+    assert!(freelance_client.get_templates(&client_address).is_empty());
+}
+
+#[test]
+fn test_parties_settle_the_escrow_to_end_a_project() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    token.approve(&client_address, &freelance_contract_address, &200, &expiration_ledger);
+
+    let prices: Vec<u128> = Vec::from_array(&env, [200_u128, 200_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+    freelance_client.fund_objectives(&project_id, &Vec::from_array(&env, [0_u128, 1_u128]));
+
+    freelance_client.propose_settlement(&project_id, &freelancer_address, &150, &50);
+    freelance_client.reject_settlement(&project_id, &client_address);
+    assert_eq!(freelance_client.get_settlement(&project_id), None);
+
+    freelance_client.propose_settlement(&project_id, &client_address, &120, &80);
+    assert_eq!(freelance_client.get_settlement(&project_id).unwrap().proposer, client_address);
+
+    freelance_client.accept_settlement(&project_id, &freelancer_address, &120, &80);
+    assert_authorized_by(&env, &freelancer_address, &freelance_contract_address, "accept_settlement", (project_id, freelancer_address.clone(), 120_u128, 80_u128).into_val(&env));
+
+    let summary = freelance_client.get_project_summary(&project_id);
+    assert_eq!(summary.status, ProjectStatus::Terminated);
+    assert_eq!(summary.escrowed, 0);
+    assert_eq!(summary.paid, 120);
+
+    assert_eq!(freelance_client.claimable(&freelancer_address).get(usdc_contract_address.clone()), Some(120));
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), Some(80));
+    assert_eq!(freelance_client.get_settlement(&project_id), None);
+}
+
+#[test]
+fn test_settlement_closes_vested_streams() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    token.approve(&client_address, &freelance_contract_address, &400, &expiration_ledger);
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+    let objective_id = freelance_client.add_stream_objective(&project_id, &400, &1000, &2000, &client_address);
+    freelance_client.fund_objective(&project_id, &objective_id);
+
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    assert_eq!(freelance_client.withdraw_vested(&project_id, &objective_id, &freelancer_address), 200);
+
+    // The settlement splits what is still escrowed, vested or not
+    freelance_client.propose_settlement(&project_id, &client_address, &100, &100);
+    freelance_client.accept_settlement(&project_id, &freelancer_address, &100, &100);
+
+    env.ledger().with_mut(|li| li.timestamp = 3000);
+    assert!(freelance_client.get_objective(&project_id, &objective_id).completed);
+    assert!(freelance_client.try_withdraw_vested(&project_id, &objective_id, &freelancer_address).is_err());
+
+    assert_eq!(freelance_client.claimable(&freelancer_address).get(usdc_contract_address.clone()), Some(300));
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), Some(100));
+    assert_eq!(token.balance(&freelance_contract_address), 400);
+}
+
+#[test]
+fn test_cancel_pays_the_kill_fee_on_funded_objectives() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    token.approve(&client_address, &freelance_contract_address, &400, &expiration_ledger);
+
+    let prices: Vec<u128> = Vec::from_array(&env, [200_u128, 200_u128, 400_u128]);
+    let terms = ProjectTerms { kill_fee_percent: 25, ..Default::default() };
+    let project_id = freelance_client.create_project_with_terms(&freelancer_address, &prices, &terms, &usdc_contract_address, &client_address);
+    assert_eq!(freelance_client.get_project(&project_id).terms, terms);
+
+    // The first objective is paid in full, the second is in progress and the third never funded
+    freelance_client.fund_objectives(&project_id, &Vec::from_array(&env, [0_u128, 1_u128]));
+    freelance_client.complete_objective(&project_id, &0, &client_address);
+
+    freelance_client.cancel_project(&project_id);
+
+    // 25% of the 100 escrowed for the second objective goes to the freelancer
+    assert_eq!(freelance_client.claimable(&freelancer_address).get(usdc_contract_address.clone()), Some(225));
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), Some(75));
+
+    let summary = freelance_client.get_project_summary(&project_id);
+    assert_eq!(summary.status, ProjectStatus::Cancelled);
+    assert_eq!(summary.paid, 225);
+    assert_eq!(summary.escrowed, 0);
+    assert_eq!(token.balance(&freelance_contract_address), 300);
+}
+
+#[test]
+fn test_freelancer_withdraws_from_a_project() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    token.approve(&client_address, &freelance_contract_address, &700, &expiration_ledger);
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128, 200_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+    let stream_id = freelance_client.add_stream_objective(&project_id, &400, &0, &1000, &client_address);
+
+    freelance_client.fund_objectives(&project_id, &Vec::from_array(&env, [0_u128, 1_u128, stream_id]));
+    freelance_client.complete_objective(&project_id, &0, &client_address);
+
+    env.ledger().with_mut(|li| li.timestamp = 250);
+    let refunded = freelance_client.withdraw_from_project(&project_id, &WithdrawalReason::ScopeChange);
+    assert_authorized_by(&env, &freelancer_address, &freelance_contract_address, "withdraw_from_project", (project_id, WithdrawalReason::ScopeChange).into_val(&env));
+
+    // The in-progress milestone and the unvested part of the stream go back to the client
+    assert_eq!(refunded, 400);
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), Some(400));
+
+    let withdrawal = freelance_client.get_withdrawal(&project_id).unwrap();
+    assert_eq!(withdrawal.reason, WithdrawalReason::ScopeChange);
+    assert_eq!(withdrawal.timestamp, 250);
+    assert_eq!(freelance_client.get_project_summary(&project_id).status, ProjectStatus::Withdrawn);
+
+    // Completed payouts and vested stream funds stay with the freelancer
+    assert_eq!(freelance_client.withdraw_vested(&project_id, &stream_id, &freelancer_address), 100);
+    assert_eq!(freelance_client.claimable(&freelancer_address).get(usdc_contract_address.clone()), Some(200));
+}
+
+#[test]
+fn test_cancel_refunds_every_uncompleted_objective() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    token.approve(&client_address, &freelance_contract_address, &800, &expiration_ledger);
+
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128, 200_u128, 300_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+    let stream_id = freelance_client.add_stream_objective(&project_id, &400, &0, &1000, &client_address);
+
+    freelance_client.fund_objectives(&project_id, &Vec::from_array(&env, [0_u128, 1_u128, stream_id]));
+    freelance_client.complete_objective(&project_id, &0, &client_address);
+
+    env.ledger().with_mut(|li| li.timestamp = 500);
+    freelance_client.cancel_project(&project_id);
+
+    let refunds: std::vec::Vec<ObjectiveEvent> = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract, topics, _)| {
+            *contract == freelance_contract_address
+                && Symbol::try_from_val(&env, &topics.get(0).unwrap()).unwrap() == Symbol::new(&env, "objective_refunded")
+        })
+        .map(|(_, _, data)| ObjectiveEvent::try_from_val(&env, &data).unwrap())
+        .collect();
+
+    // The in-progress milestone and the unvested half of the stream, nothing for the unfunded one
+    assert_eq!(refunds, std::vec![
+        ObjectiveEvent { version: EVENT_VERSION, objective_id: 1, amount: 100 },
+        ObjectiveEvent { version: EVENT_VERSION, objective_id: stream_id, amount: 200 },
+    ]);
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), Some(300));
+
+    let summary = freelance_client.get_project_summary(&project_id);
+    assert_eq!(summary.escrowed, 200);
+    assert_eq!(summary.refundable, 0);
+    assert_eq!(freelance_client.get_objective(&project_id, &1).half_paid, 0);
+}
+
+#[test]
+fn test_completed_objectives_hold_back_a_warranty_share() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    token.mint(&client_address, &1000);
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    token.approve(&client_address, &freelance_contract_address, &600, &expiration_ledger);
+
+    let prices: Vec<u128> = Vec::from_array(&env, [200_u128, 400_u128]);
+    let terms = ProjectTerms { holdback_percent: 10, holdback_days: 7, ..Default::default() };
+    let project_id = freelance_client.create_project_with_terms(&freelancer_address, &prices, &terms, &usdc_contract_address, &client_address);
+
+    let objective_ids = Vec::from_array(&env, [0_u128, 1_u128]);
+    freelance_client.fund_objectives(&project_id, &objective_ids);
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    freelance_client.complete_objectives(&project_id, &objective_ids, &client_address);
+
+    // 10% of each price stays in escrow for a week
+    assert_eq!(freelance_client.claimable(&freelancer_address).get(usdc_contract_address.clone()), Some(540));
+    let release_at = 1000 + 7 * 24 * 60 * 60;
+    let pending = freelance_client.get_pending_holdbacks(&project_id);
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending.get(0).unwrap().amount, 20);
+    assert_eq!(pending.get(1).unwrap().amount, 40);
+    assert_eq!(pending.get(0).unwrap().release_at, release_at);
+
+    let summary = freelance_client.get_project_summary(&project_id);
+    assert_eq!(summary.paid, 540);
+    assert_eq!(summary.escrowed, 60);
+
+    // The client finds a defect in the second objective before the window closes
+    let claim_hash = BytesN::from_array(&env, &[7; 32]);
+    freelance_client.raise_defect_claim(&project_id, &1, &claim_hash);
+    assert!(freelance_client.get_holdback(&project_id, &1).unwrap().claimed);
+
+    env.ledger().with_mut(|li| li.timestamp = release_at);
+    assert_eq!(freelance_client.release_holdback(&project_id, &0), 20);
+    assert_eq!(freelance_client.claimable(&freelancer_address).get(usdc_contract_address.clone()), Some(560));
+
+    // The freelancer accepts the claim, the holdback goes back to the client
+    assert_eq!(freelance_client.resolve_defect_claim(&project_id, &1, &freelancer_address), 40);
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), Some(40));
+
+    assert_eq!(freelance_client.get_pending_holdbacks(&project_id).len(), 0);
+    let summary = freelance_client.get_project_summary(&project_id);
+    assert_eq!(summary.paid, 560);
+    assert_eq!(summary.escrowed, 0);
+    assert_eq!(token.balance(&freelance_contract_address), 600);
+
+    // A claim neither party gives in on is split by agreement, also after completion
+    token.approve(&client_address, &freelance_contract_address, &100, &expiration_ledger);
+    let prices: Vec<u128> = Vec::from_array(&env, [100_u128]);
+    let disputed_id = freelance_client.create_project_with_terms(&freelancer_address, &prices, &terms, &usdc_contract_address, &client_address);
+    freelance_client.fund_objective(&disputed_id, &0);
+    freelance_client.complete_objective(&disputed_id, &0, &client_address);
+    freelance_client.raise_defect_claim(&disputed_id, &0, &claim_hash);
+    freelance_client.complete_project(&disputed_id);
+
+    env.ledger().with_mut(|li| li.timestamp = release_at + 10000);
+    assert!(freelance_client.try_release_holdback(&disputed_id, &0).is_err());
+    assert!(freelance_client.try_propose_settlement(&disputed_id, &freelancer_address, &6, &4).is_err());
+    assert!(freelance_client.try_propose_claim_settlement(&disputed_id, &0, &freelancer_address, &6, &5).is_err());
+
+    freelance_client.propose_claim_settlement(&disputed_id, &0, &freelancer_address, &6, &4);
+    assert_eq!(freelance_client.get_claim_settlement(&disputed_id, &0).unwrap().proposer, freelancer_address);
+    assert!(freelance_client.try_accept_claim_settlement(&disputed_id, &0, &freelancer_address, &6, &4).is_err());
+    assert!(freelance_client.try_accept_claim_settlement(&disputed_id, &0, &client_address, &10, &0).is_err());
+    freelance_client.accept_claim_settlement(&disputed_id, &0, &client_address, &6, &4);
+
+    assert_eq!(freelance_client.claimable(&freelancer_address).get(usdc_contract_address.clone()), Some(560 + 90 + 6));
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), Some(40 + 4));
+    assert_eq!(freelance_client.get_holdback(&disputed_id, &0), None);
+    assert_eq!(freelance_client.get_claim_settlement(&disputed_id, &0), None);
+    assert_eq!(token.balance(&freelance_contract_address), 700);
+}
+
+#[test]
+fn test_authorized_funders_sponsor_objectives_and_get_refunds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let grants_program = Address::generate(&env);
+    let parent_company = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    for funder in [&client_address, &grants_program, &parent_company] {
+        token.mint(funder, &1000);
+        token.approve(funder, &freelance_contract_address, &1000, &expiration_ledger);
+    }
+
+    let prices: Vec<u128> = Vec::from_array(&env, [200_u128, 400_u128, 100_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+
+    freelance_client.add_funder(&project_id, &grants_program);
+    freelance_client.add_funder(&project_id, &parent_company);
+    assert!(freelance_client.is_funder(&project_id, &grants_program));
+
+    freelance_client.sponsor_objective(&project_id, &0, &grants_program);
+    assert_authorized_by(&env, &grants_program, &freelance_contract_address, "sponsor_objective", (project_id, 0_u128, grants_program.clone()).into_val(&env));
+    freelance_client.sponsor_objective(&project_id, &1, &parent_company);
+    freelance_client.fund_objective(&project_id, &2);
+
+    assert_eq!(token.balance(&grants_program), 900);
+    assert_eq!(token.balance(&parent_company), 800);
+    assert_eq!(freelance_client.get_contributions(&project_id, &0).get(grants_program.clone()), Some(100));
+    assert!(freelance_client.get_contributions(&project_id, &2).is_empty());
+
+    freelance_client.remove_funder(&project_id, &parent_company);
+    assert!(!freelance_client.is_funder(&project_id, &parent_company));
+
+    // Each funder gets back what it escrowed, the client only what it paid itself
+    freelance_client.cancel_project(&project_id);
+    assert_eq!(freelance_client.claimable(&grants_program).get(usdc_contract_address.clone()), Some(100));
+    assert_eq!(freelance_client.claimable(&parent_company).get(usdc_contract_address.clone()), Some(200));
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), Some(50));
+
+    freelance_client.withdraw(&parent_company, &usdc_contract_address);
+    assert_eq!(token.balance(&parent_company), 1000);
+}
+
+#[test]
+fn test_crowdfunded_objectives_activate_when_fully_funded() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let backer_a = Address::generate(&env);
+    let backer_b = Address::generate(&env);
+    let backer_c = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    for backer in [&backer_a, &backer_b, &backer_c] {
+        token.mint(backer, &1000);
+        token.approve(backer, &freelance_contract_address, &1000, &expiration_ledger);
+    }
+
+    let prices: Vec<u128> = Vec::from_array(&env, [400_u128, 100_u128, 300_u128, 100_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    for objective_id in 0..4_u128 {
+        let deadline = if objective_id == 1 { 2000 } else { 5000 };
+        freelance_client.open_crowdfunding(&project_id, &objective_id, &deadline);
+    }
+
+    // The last contribution only takes what is missing and activates the objective
+    freelance_client.contribute(&project_id, &0, &backer_a, &100);
+    freelance_client.contribute(&project_id, &0, &backer_b, &250);
+    assert_eq!(freelance_client.get_crowdfund(&project_id, &0).unwrap().raised, 350);
+    assert_eq!(freelance_client.get_objective(&project_id, &0).half_paid, 0);
+    assert_eq!(freelance_client.contribute(&project_id, &0, &backer_c, &200), 50);
+    assert_eq!(freelance_client.get_crowdfund(&project_id, &0), None);
+    assert_eq!(freelance_client.get_objective(&project_id, &0).half_paid, 400);
+
+    // Nothing is pulled from the client on completion
+    freelance_client.complete_objective(&project_id, &0, &client_address);
+    assert_eq!(freelance_client.claimable(&freelancer_address).get(usdc_contract_address.clone()), Some(400));
+
+    // The second objective misses its deadline and backers get their contributions back
+    freelance_client.contribute(&project_id, &1, &backer_a, &30);
+    freelance_client.contribute(&project_id, &1, &backer_b, &20);
+    env.ledger().with_mut(|li| li.timestamp = 2000);
+    assert!(freelance_client.try_contribute(&project_id, &1, &backer_c, &50).is_err());
+    assert_eq!(freelance_client.expire_crowdfunding(&project_id, &1), 50);
+    assert_eq!(freelance_client.claimable(&backer_a).get(usdc_contract_address.clone()), Some(30));
+    assert_eq!(freelance_client.claimable(&backer_b).get(usdc_contract_address.clone()), Some(20));
+    assert!(freelance_client.get_contributions(&project_id, &1).is_empty());
+
+    // One funded and one partly funded objective are open when the client cancels
+    freelance_client.contribute(&project_id, &2, &backer_a, &100);
+    freelance_client.contribute(&project_id, &2, &backer_b, &200);
+    freelance_client.contribute(&project_id, &3, &backer_c, &60);
+
+    let summary = freelance_client.get_project_summary(&project_id);
+    assert_eq!(summary.escrowed, 360);
+    assert_eq!(summary.remaining_to_fund, 100 + 40);
+
+    freelance_client.cancel_project(&project_id);
+    assert_eq!(freelance_client.claimable(&backer_a).get(usdc_contract_address.clone()), Some(130));
+    assert_eq!(freelance_client.claimable(&backer_b).get(usdc_contract_address.clone()), Some(220));
+    assert_eq!(freelance_client.claimable(&backer_c).get(usdc_contract_address.clone()), Some(60));
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), None);
+    assert_eq!(freelance_client.get_crowdfund(&project_id, &3), None);
+    assert_eq!(token.balance(&freelance_contract_address), 810);
+}
+
+#[test]
+fn test_projects_only_pay_out_their_own_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    let accomplice = Address::generate(&env);
+    let usdc = create_token(&env, &admin1);
+    let worthless = create_token(&env, &attacker);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    usdc.mint(&client_address, &1000);
+    usdc.approve(&client_address, &freelance_contract_address, &1000, &expiration_ledger);
+    worthless.mint(&attacker, &1000);
+    worthless.approve(&attacker, &freelance_contract_address, &1000, &expiration_ledger);
+
+    let prices: Vec<u128> = Vec::from_array(&env, [1000_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc.address, &client_address);
+    freelance_client.fund_objective(&project_id, &0);
+    assert_eq!(freelance_client.get_project(&project_id).token, usdc.address);
+
+    // Funding and cancelling a project escrowed in another token only refunds that token
+    let attacker_project = freelance_client.create_project(&accomplice, &prices, &worthless.address, &attacker);
+    freelance_client.fund_objective(&attacker_project, &0);
+    freelance_client.cancel_project(&attacker_project);
+
+    let claimable = freelance_client.claimable(&attacker);
+    assert_eq!(claimable.get(worthless.address.clone()), Some(500));
+    assert_eq!(claimable.get(usdc.address.clone()), None);
+    assert!(freelance_client.try_withdraw(&attacker, &usdc.address).is_err());
+    assert_eq!(usdc.balance(&freelance_contract_address), 500);
+}
+
+#[test]
+fn test_settlements_and_conceded_claims_refund_sponsors() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let sponsor = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    for funder in [&client_address, &sponsor] {
+        token.mint(funder, &1000);
+        token.approve(funder, &freelance_contract_address, &1000, &expiration_ledger);
+    }
+
+    // The sponsor and the client each escrow one objective, then the parties settle half and half
+    let prices: Vec<u128> = Vec::from_array(&env, [200_u128, 200_u128]);
+    let project_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+    freelance_client.add_funder(&project_id, &sponsor);
+    freelance_client.sponsor_objective(&project_id, &0, &sponsor);
+    freelance_client.fund_objective(&project_id, &1);
+
+    freelance_client.propose_settlement(&project_id, &client_address, &100, &100);
+    freelance_client.accept_settlement(&project_id, &freelancer_address, &100, &100);
+
+    assert_eq!(freelance_client.claimable(&sponsor).get(usdc_contract_address.clone()), Some(50));
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), Some(50));
+    assert!(freelance_client.get_contributions(&project_id, &0).is_empty());
+
+    // A conceded defect claim is split between the sponsor and the client who paid the rest
+    let terms = ProjectTerms { holdback_percent: 10, holdback_days: 7, ..Default::default() };
+    let prices: Vec<u128> = Vec::from_array(&env, [200_u128]);
+    let project_id = freelance_client.create_project_with_terms(&freelancer_address, &prices, &terms, &usdc_contract_address, &client_address);
+    freelance_client.add_funder(&project_id, &sponsor);
+    freelance_client.sponsor_objective(&project_id, &0, &sponsor);
+    freelance_client.complete_objective(&project_id, &0, &client_address);
+    freelance_client.complete_project(&project_id);
+
+    freelance_client.raise_defect_claim(&project_id, &0, &BytesN::from_array(&env, &[3; 32]));
+    freelance_client.resolve_defect_claim(&project_id, &0, &freelancer_address);
+
+    assert_eq!(freelance_client.claimable(&sponsor).get(usdc_contract_address.clone()), Some(60));
+    assert_eq!(freelance_client.claimable(&client_address).get(usdc_contract_address.clone()), Some(60));
+    assert!(freelance_client.get_contributions(&project_id, &0).is_empty());
+}
+
+#[test]
+fn test_migration_moves_records_keyed_by_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin1 = Address::generate(&env);
+    let client_address = Address::generate(&env);
+    let freelancer_address = Address::generate(&env);
+    let member = Address::generate(&env);
+    let org_account = Address::generate(&env);
+    let token = create_token(&env, &admin1);
+
+    let freelance_contract_address = env.register_contract(None, FreelanceContract);
+    let freelance_client = FreelanceContractClient::new(&env, &freelance_contract_address);
+
+    let usdc_contract_address = token.address.clone();
+    let expiration_ledger = env.ledger().sequence() + 1000;
+    for funder in [&client_address, &member] {
+        token.mint(funder, &1000);
+        token.approve(funder, &freelance_contract_address, &1000, &expiration_ledger);
+    }
+
+    let profile = UserProfile {
+        profile_hash: BytesN::from_array(&env, &[1; 32]),
+        profile_uri: String::from_str(&env, "ipfs://profile"),
+        role: UserRole::Both,
+        skills: Vec::new(&env),
+        hourly_rate: 0,
+    };
+    freelance_client.register(&freelancer_address, &String::from_str(&env, "rated_freelancer"), &profile);
+    freelance_client.register(&member, &String::from_str(&env, "org_member"), &profile);
+
+    let mut members = Map::new(&env);
+    members.set(member.clone(), OrgRole::Owner);
+    let org_id = freelance_client.create_organization(&org_account, &members, &1);
+
+    // The freelancer rates a paid project, then moves to a new key
+    let prices: Vec<u128> = Vec::from_array(&env, [200_u128, 200_u128]);
+    let rated_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+    freelance_client.fund_objective(&rated_id, &0);
+    freelance_client.complete_objective(&rated_id, &0, &client_address);
+    freelance_client.cancel_project(&rated_id);
+    freelance_client.rate_project(&rated_id, &4, &BytesN::from_array(&env, &[0; 32]), &freelancer_address);
+
+    let settled_id = freelance_client.create_project(&freelancer_address, &prices, &usdc_contract_address, &client_address);
+    freelance_client.fund_objective(&settled_id, &0);
+    freelance_client.propose_settlement(&settled_id, &freelancer_address, &50, &50);
+
+    let new_freelancer = Address::generate(&env);
+    freelance_client.migrate_account(&freelancer_address, &new_freelancer);
+    assert_eq!(freelance_client.get_rating(&rated_id, &new_freelancer).unwrap().rater, new_freelancer);
+    assert!(freelance_client.try_rate_project(&rated_id, &1, &BytesN::from_array(&env, &[0; 32]), &new_freelancer).is_err());
+    assert_eq!(freelance_client.get_reputation(&client_address).rating_count, 1);
+
+    // The proposer still cannot accept their own settlement from the new key
+    assert_eq!(freelance_client.get_settlement(&settled_id).unwrap().proposer, new_freelancer);
+    assert!(freelance_client.try_accept_settlement(&settled_id, &new_freelancer, &50, &50).is_err());
+    freelance_client.accept_settlement(&settled_id, &client_address, &50, &50);
+
+    // A delegate and funder moves with its permissions, contributions and organization role
+    let project_id = freelance_client.create_project(&new_freelancer, &prices, &usdc_contract_address, &client_address);
+    let permissions = DelegatePermissions { approve_objectives: true, add_objectives: false, review_timesheets: false };
+    freelance_client.set_delegate(&project_id, &member, &permissions);
+    freelance_client.add_funder(&project_id, &member);
+    freelance_client.sponsor_objective(&project_id, &0, &member);
+
+    let second_owner = Address::generate(&env);
+    freelance_client.set_org_member(&org_id, &second_owner, &OrgRole::Owner, &member);
+    freelance_client.set_org_threshold(&org_id, &2, &member);
+    let org_project_id = freelance_client.create_project(&new_freelancer, &prices, &usdc_contract_address, &org_account);
+    let fund = OrgAction::FundObjective(0);
+    assert!(!freelance_client.approve_org_action(&org_project_id, &fund, &member));
+
+    let new_member = Address::generate(&env);
+    freelance_client.migrate_account(&member, &new_member);
+    assert_eq!(freelance_client.get_delegate(&project_id, &member), None);
+    assert_eq!(freelance_client.get_delegate(&project_id, &new_member), Some(permissions));
+    assert!(freelance_client.is_funder(&project_id, &new_member));
+    assert_eq!(freelance_client.get_contributions(&project_id, &0).get(new_member.clone()), Some(100));
+    assert_eq!(freelance_client.get_organization(&org_id).members.get(new_member.clone()), Some(OrgRole::Owner));
+    assert!(freelance_client.try_approve_org_action(&org_project_id, &fund, &new_member).is_err());
+
+    // Migration only walks the user's own projects and organizations, and the indexes move too
+    env.as_contract(&freelance_contract_address, || {
+        let project_ids: Option<Vec<u128>> = env.storage().persistent().get(&DataKey::AddressProjects(new_member.clone()));
+        assert_eq!(project_ids, Some(Vec::from_array(&env, [project_id])));
+        assert!(!env.storage().persistent().has(&DataKey::AddressProjects(member.clone())));
+
+        let org_ids: Option<Vec<u64>> = env.storage().persistent().get(&DataKey::MemberOrgs(new_member.clone()));
+        assert_eq!(org_ids, Some(Vec::from_array(&env, [org_id])));
+        assert!(!env.storage().persistent().has(&DataKey::MemberOrgs(member.clone())));
+    });
+
+    freelance_client.cancel_project(&project_id);
+    assert_eq!(freelance_client.claimable(&new_member).get(usdc_contract_address.clone()), Some(100));
+    assert_eq!(freelance_client.claimable(&member).get(usdc_contract_address.clone()), None);
+}
@@ -0,0 +1,182 @@
+use soroban_sdk::{Address, Env, String, Vec};
+
+use crate::claims::move_claimable;
+use crate::delegate::move_delegate;
+use crate::funding::move_funder;
+use crate::organization::move_organizations;
+use crate::reputation::{move_rating, move_reputation};
+use crate::storage::{add_address_project, get_project, read_address_projects, read_persistent, rebind_project_parties, write_persistent};
+use crate::storage_types::{DataKey, RecoveryConfig, Settlement, User, UserRole};
+use crate::template::move_templates;
+
+pub const MIN_HANDLE_LENGTH: u32 = 3;
+pub const MAX_HANDLE_LENGTH: u32 = 32;
+
+// Lowercases the handle and checks it only uses [a-z0-9_], so lookups are
+// case insensitive and every handle maps to a single user.
+pub fn normalize_handle(e: &Env, handle: &String) -> String {
+    let len = handle.len();
+    if !(MIN_HANDLE_LENGTH..=MAX_HANDLE_LENGTH).contains(&len) {
+        panic!("Handle must be between 3 and 32 characters");
+    }
+
+    let mut buf = [0u8; MAX_HANDLE_LENGTH as usize];
+    let buf = &mut buf[..len as usize];
+    handle.copy_into_slice(buf);
+
+    for c in buf.iter_mut() {
+        c.make_ascii_lowercase();
+        if !(c.is_ascii_lowercase() || c.is_ascii_digit() || *c == b'_') {
+            panic!("Handle can only contain letters, digits and underscores");
+        }
+    }
+
+    String::from_bytes(e, buf)
+}
+
+pub fn read_user(e: &Env, user_address: &Address) -> Option<User> {
+    read_persistent(e, &DataKey::User(user_address.clone()))
+}
+
+pub fn read_user_by_id(e: &Env, user_id: u64) -> Option<User> {
+    let user_address: Address = read_persistent(e, &DataKey::UserId(user_id))?;
+    read_user(e, &user_address)
+}
+
+// Approvals are kept per candidate address. A guardian backs one candidate at a
+// time, so switching only moves their own approval and leaves the others intact.
+// Returns how many guardians now back `candidate`.
+pub fn approve_recovery_candidate(e: &Env, lost: &Address, candidate: &Address, guardian: &Address) -> u32 {
+    let candidates_key = DataKey::RecoveryCandidates(lost.clone());
+    let mut candidates: Vec<Address> = read_persistent(e, &candidates_key).unwrap_or(Vec::new(e));
+
+    for other in candidates.clone().iter() {
+        let key = DataKey::RecoveryRequest(lost.clone(), other.clone());
+        let mut approvals: Vec<Address> = read_persistent(e, &key).unwrap_or(Vec::new(e));
+        let index = match approvals.first_index_of(guardian) {
+            Some(index) => index,
+            None => continue,
+        };
+
+        if other == *candidate {
+            panic!("Guardian already approved this recovery");
+        }
+
+        approvals.remove(index);
+        if approvals.is_empty() {
+            e.storage().persistent().remove(&key);
+            candidates.remove(candidates.first_index_of(&other).unwrap());
+        } else {
+            write_persistent(e, &key, &approvals);
+        }
+    }
+
+    let key = DataKey::RecoveryRequest(lost.clone(), candidate.clone());
+    let mut approvals: Vec<Address> = read_persistent(e, &key).unwrap_or(Vec::new(e));
+    approvals.push_back(guardian.clone());
+    write_persistent(e, &key, &approvals);
+
+    if !candidates.contains(candidate) {
+        candidates.push_back(candidate.clone());
+    }
+    write_persistent(e, &candidates_key, &candidates);
+
+    approvals.len()
+}
+
+pub fn clear_recovery_requests(e: &Env, lost: &Address) {
+    let candidates_key = DataKey::RecoveryCandidates(lost.clone());
+    let candidates: Vec<Address> = match read_persistent(e, &candidates_key) {
+        Some(candidates) => candidates,
+        None => return,
+    };
+
+    for candidate in candidates.iter() {
+        e.storage().persistent().remove(&DataKey::RecoveryRequest(lost.clone(), candidate));
+    }
+    e.storage().persistent().remove(&candidates_key);
+}
+
+// A pending proposal stays with its proposer so they still cannot accept it
+fn move_proposer(e: &Env, key: &DataKey, old: &Address, new: &Address) {
+    if let Some(mut settlement) = read_persistent::<Settlement>(e, key) {
+        if settlement.proposer == *old {
+            settlement.proposer = new.clone();
+            write_persistent(e, key, &settlement);
+        }
+    }
+}
+
+pub fn has_role(user: &User, role: &UserRole) -> bool {
+    match role {
+        UserRole::Both => user.profile.role == UserRole::Both,
+        _ => user.profile.role == *role || user.profile.role == UserRole::Both,
+    }
+}
+
+// Rebinds a registered user's profile, handle, reputation, recovery settings,
+// organizations, project roles and every per-project record from `old` to `new`.
+// Returns how many projects moved.
+pub fn migrate_user(e: &Env, old: &Address, new: &Address) -> u32 {
+    let mut user = match read_user(e, old) {
+        Some(user) => user,
+        None => panic!("User not found"),
+    };
+
+    if read_user(e, new).is_some() {
+        panic!("New address is already registered");
+    }
+
+    if e.storage().persistent().has(&DataKey::BlockedUser(new.clone())) {
+        panic!("New address is not allowed to register");
+    }
+
+    if e.storage().persistent().has(&DataKey::OrgAccount(new.clone())) {
+        panic!("New address is already an organization account");
+    }
+
+    user.user = new.clone();
+    e.storage().persistent().remove(&DataKey::User(old.clone()));
+    write_persistent(e, &DataKey::User(new.clone()), &user);
+    write_persistent(e, &DataKey::UserId(user.id), new);
+    write_persistent(e, &DataKey::Handle(user.handle.clone()), new);
+
+    if let Some(user_reg_id) = read_persistent::<u32>(e, &DataKey::UserRegId(old.clone())) {
+        e.storage().persistent().remove(&DataKey::UserRegId(old.clone()));
+        write_persistent(e, &DataKey::UserRegId(new.clone()), &user_reg_id);
+    }
+
+    if let Some(recovery) = read_persistent::<RecoveryConfig>(e, &DataKey::Recovery(old.clone())) {
+        e.storage().persistent().remove(&DataKey::Recovery(old.clone()));
+        write_persistent(e, &DataKey::Recovery(new.clone()), &recovery);
+    }
+    clear_recovery_requests(e, old);
+
+    move_reputation(e, old, new);
+    move_claimable(e, old, new);
+    move_templates(e, old, new);
+    move_organizations(e, old, new);
+
+    // Ratings, delegates and funders are keyed by address on the user's projects
+    let mut rebound: u32 = 0;
+    for project_id in read_address_projects(e, old).iter() {
+        if rebind_project_parties(e, project_id, old, new) {
+            rebound += 1;
+        }
+
+        let (project, _) = get_project(e, project_id);
+        move_rating(e, project_id, &project, old, new);
+        move_delegate(e, project_id, old, new);
+        move_funder(e, project_id, &project, old, new);
+
+        move_proposer(e, &DataKey::Settlement(project_id), old, new);
+        for (objective_id, _) in project.objectives.iter() {
+            move_proposer(e, &DataKey::ClaimSettlement(project_id, objective_id), old, new);
+        }
+
+        add_address_project(e, new, project_id);
+    }
+    e.storage().persistent().remove(&DataKey::AddressProjects(old.clone()));
+
+    rebound
+}